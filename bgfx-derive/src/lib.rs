@@ -0,0 +1,308 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+//! Derive macros for the [`bgfx`] crate.
+//!
+//! These are re-exported by `bgfx` when its `derive` feature is enabled, and should be used
+//! through there. See `bgfx::Vertex` for documentation.
+//!
+//! [`bgfx`]: https://github.com/rhoot/bgfx-rs
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as Tokens;
+use syn::{Attribute, Data, DeriveInput, Error, Expr, Field, Ident, Lit, LitInt, LitStr, Type};
+use syn::spanned::Spanned;
+
+/// Attribute types, along with the number of bytes they occupy for 1-4 components, and whether
+/// they are integer types. Matches the sizes used by `bgfx::VertexDeclBuilder::add`.
+const ATTRIB_TYPES: &'static [(&'static str, [usize; 4], bool)] = &[
+    ("Uint8", [1, 2, 4, 4], true),
+    ("Uint10", [4, 4, 4, 4], true),
+    ("Int16", [2, 4, 8, 8], true),
+    ("Half", [2, 4, 8, 8], false),
+    ("Float", [4, 8, 12, 16], false),
+];
+
+/// Derives `bgfx::Vertex`.
+#[proc_macro_derive(Vertex, attributes(bgfx))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand_vertex(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => compile_errors(err).into(),
+    }
+}
+
+/// Reports errors through `compile_error!`. `Error::to_compile_error` refers to it through
+/// `::core`, which does not resolve in 2015 edition crates.
+fn compile_errors(err: Error) -> Tokens {
+    let errors = err.into_iter().map(|err| {
+        let message = err.to_string();
+        quote_spanned! { err.span() => compile_error!(#message); }
+    });
+    quote! { #(#errors)* }
+}
+
+/// Options set through `#[bgfx(...)]` on a field.
+#[derive(Default)]
+struct FieldOptions {
+    attrib: Option<LitStr>,
+    count: Option<LitInt>,
+    ty: Option<LitStr>,
+    normalized: bool,
+    as_int: bool,
+    skip: bool,
+}
+
+impl FieldOptions {
+
+    fn parse(attrs: &[Attribute]) -> syn::Result<FieldOptions> {
+        let mut options = FieldOptions::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("bgfx")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("attrib") {
+                    options.attrib = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("count") {
+                    options.count = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("ty") {
+                    options.ty = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("normalized") {
+                    options.normalized = true;
+                } else if meta.path.is_ident("as_int") {
+                    options.as_int = true;
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                } else {
+                    return Err(meta.error("unknown bgfx attribute, expected one of `attrib`, \
+                                           `count`, `ty`, `normalized`, `as_int` or `skip`"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+
+}
+
+fn expand_vertex(input: &DeriveInput) -> syn::Result<Tokens> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => return Err(Error::new(input.ident.span(), "Vertex can only be derived for structs")),
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(input.generics.span(),
+                              "Vertex can not be derived for generic structs"));
+    }
+
+    if !has_fixed_layout(&input.attrs)? {
+        return Err(Error::new(input.ident.span(),
+                              "Vertex can only be derived for `#[repr(C)]` or \
+                               `#[repr(C, packed)]` structs"));
+    }
+
+    let mut calls = Vec::new();
+    let mut checks = Vec::new();
+    let mut sizes = Vec::new();
+    let mut pod_checks = Vec::new();
+
+    for field in fields {
+        let ty = &field.ty;
+        let options = FieldOptions::parse(&field.attrs)?;
+
+        pod_checks.push(quote_spanned! { ty.span() => assert_pod::<#ty>(); });
+
+        let size = if options.skip {
+            calls.push(quote! { .skip(::std::mem::size_of::<#ty>() as u8) });
+            quote! { ::std::mem::size_of::<#ty>() }
+        } else {
+            let (call, size) = attrib_call(field, &options)?;
+            calls.push(call);
+            checks.push(quote_spanned! { ty.span() =>
+                const _: [(); #size] = [(); ::std::mem::size_of::<#ty>()];
+            });
+            quote! { #size }
+        };
+
+        sizes.push(size);
+    }
+
+    let ident = &input.ident;
+    let stride_check = quote_spanned! { ident.span() =>
+        const _: [(); ::std::mem::size_of::<#ident>()] = [(); 0 #(+ #sizes)*];
+    };
+
+    // The stride check guarantees there is no padding, so the struct is plain data as long as
+    // all of its fields are.
+    Ok(quote! {
+        impl ::bgfx::Vertex for #ident {
+            fn decl() -> ::bgfx::VertexDecl {
+                ::bgfx::VertexDecl::new(None)
+                    #(#calls)*
+                    .end()
+            }
+        }
+
+        unsafe impl ::bgfx::Pod for #ident {}
+
+        const _: () = {
+            fn assert_pod<T: ::bgfx::Pod>() {}
+
+            #[allow(dead_code)]
+            fn assert_fields() {
+                #(#pod_checks)*
+            }
+        };
+
+        #(#checks)*
+        #stride_check
+    })
+}
+
+/// Returns whether the struct is `#[repr(C)]`, possibly along with `packed`. A bare
+/// `#[repr(packed)]` leaves the field order up to the compiler, so it is not enough.
+fn has_fixed_layout(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut fixed = false;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                fixed = true;
+            }
+
+            // Skip arguments such as the alignment in `packed(2)` or `align(16)`.
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                parenthesized!(content in meta.input);
+                content.parse::<Tokens>()?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(fixed)
+}
+
+/// Builds the `VertexDeclBuilder::add` call for a field, along with the number of bytes the
+/// attribute occupies.
+fn attrib_call(field: &Field, options: &FieldOptions) -> syn::Result<(Tokens, usize)> {
+    let attrib = match options.attrib {
+        Some(ref attrib) => attrib.parse::<Ident>()?,
+        None => {
+            return Err(Error::new(field.span(),
+                                  "missing `#[bgfx(attrib = \"...\")]` or `#[bgfx(skip)]`"))
+        }
+    };
+
+    let inferred = infer_attrib(&field.ty);
+    let (name, sizes, integer) = match options.ty {
+        Some(ref ty) => {
+            match ATTRIB_TYPES.iter().find(|kind| kind.0 == ty.value()) {
+                Some(kind) => *kind,
+                None => return Err(Error::new(ty.span(), "unknown attribute type")),
+            }
+        }
+        None => {
+            match inferred {
+                Some((name, _)) => *ATTRIB_TYPES.iter().find(|kind| kind.0 == name).unwrap(),
+                None => {
+                    return Err(Error::new(field.ty.span(),
+                                          "can not infer the attribute type of this field, \
+                                           specify it with `#[bgfx(ty = \"...\")]`"))
+                }
+            }
+        }
+    };
+
+    let count = match options.count {
+        Some(ref count) => count.base10_parse::<u8>()?,
+        None if options.ty.is_some() => array_len(&field.ty).unwrap_or(1),
+        None => inferred.unwrap().1,
+    };
+
+    if count < 1 || count > 4 {
+        let span = options.count.as_ref().map_or(field.ty.span(), |count| count.span());
+        return Err(Error::new(span, "attributes must have between 1 and 4 components"));
+    }
+
+    if !integer && options.normalized {
+        return Err(Error::new(field.span(), "only integer attributes can be normalized"));
+    }
+
+    if !integer && options.as_int {
+        return Err(Error::new(field.span(), "only integer attributes can be passed as integers"));
+    }
+
+    let kind = Ident::new(name, attrib.span());
+    let mut flags = Vec::new();
+    if options.normalized {
+        flags.push(quote!(::bgfx::ATTRIB_NORMALIZED));
+    }
+    if options.as_int {
+        flags.push(quote!(::bgfx::ATTRIB_AS_INT));
+    }
+    if flags.is_empty() {
+        flags.push(quote!(::bgfx::ATTRIB_NONE));
+    }
+    let call = quote! {
+        .add(::bgfx::Attrib::#attrib, #count, ::bgfx::AttribType::#kind, #(#flags)|*)
+    };
+    Ok((call, sizes[count as usize - 1]))
+}
+
+/// Infers the attribute type and component count of a field from its type. Scalars and arrays of
+/// `f32`, `u8` and `i16` are supported, as well as `u32`, which is taken to be four packed `u8`
+/// components, such as an ABGR color.
+fn infer_attrib(ty: &Type) -> Option<(&'static str, u8)> {
+    match *ty {
+        Type::Array(ref array) => {
+            match scalar_attrib(&array.elem) {
+                Some((name, 1)) => array_len(ty).map(|len| (name, len)),
+                _ => None,
+            }
+        }
+        _ => scalar_attrib(ty),
+    }
+}
+
+fn scalar_attrib(ty: &Type) -> Option<(&'static str, u8)> {
+    let ident = match *ty {
+        Type::Path(ref path) if path.qself.is_none() => path.path.get_ident(),
+        _ => None,
+    };
+
+    match ident.map(|ident| ident.to_string()) {
+        Some(ref ident) if ident == "f32" => Some(("Float", 1)),
+        Some(ref ident) if ident == "u8" => Some(("Uint8", 1)),
+        Some(ref ident) if ident == "i16" => Some(("Int16", 1)),
+        Some(ref ident) if ident == "u32" => Some(("Uint8", 4)),
+        _ => None,
+    }
+}
+
+fn array_len(ty: &Type) -> Option<u8> {
+    match *ty {
+        Type::Array(ref array) => {
+            match array.len {
+                Expr::Lit(ref expr) => {
+                    match expr.lit {
+                        Lit::Int(ref len) => len.base10_parse().ok(),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
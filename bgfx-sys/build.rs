@@ -2,6 +2,7 @@
 // License: http://opensource.org/licenses/ISC
 
 use std::env;
+use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
@@ -98,16 +99,45 @@ fn build_gmake(bitness: u32, profile: &str, platform: &str) {
         panic!("Failed to generate makefiles.");
     }
 
+    // Wayland builds use EGL with GLES instead of GLX with desktop GL
+    let wayland = platform == "unknown-linux" && env::var("CARGO_FEATURE_WAYLAND").is_ok();
+
     // C flags
-    let cflags = if platform == "pc-windows" && bitness == 32 {
-        "-fPIC -DBGFX_CONFIG_MULTITHREADED=1 -mincoming-stack-boundary=2"
-    } else {
-        "-fPIC -DBGFX_CONFIG_MULTITHREADED=1"
-    };
+    let mut cflags = String::from("-fPIC -DBGFX_CONFIG_MULTITHREADED=1");
+
+    if platform == "pc-windows" && bitness == 32 {
+        cflags.push_str(" -mincoming-stack-boundary=2");
+    }
+
+    if wayland {
+        cflags.push_str(" -DWL_EGL_PLATFORM=1 -DBGFX_USE_EGL=1");
+        cflags.push_str(" -DBGFX_CONFIG_RENDERER_OPENGL=0 -DBGFX_CONFIG_RENDERER_OPENGLES=30");
+    }
+
+    // Emitting any rerun directive replaces the default of rerunning on any change in the package,
+    // so the bgfx sources have to be listed as well.
+    println!("cargo:rerun-if-env-changed=CARGO_FEATURE_WAYLAND");
+    println!("cargo:rerun-if-changed=build.rs");
+    for dir in &["bgfx/include", "bgfx/src", "bimg/include", "bimg/src", "bx/include", "bx/src"] {
+        println!("cargo:rerun-if-changed={}", dir);
+    }
+
+    // make does not track the C flags, so objects built with other flags, such as those for
+    // another windowing system, would be linked as is. Start from a clean build whenever the
+    // flags change.
+    let mut build_dir = PathBuf::from("bgfx");
+    build_dir.push(".build");
+    build_dir.push(&output_name);
+
+    let stamp = build_dir.join("bgfx-sys-cflags");
+    if fs::read_to_string(&stamp).unwrap_or_default() != cflags {
+        let _ = fs::remove_dir_all(build_dir.join("obj"));
+        let _ = fs::remove_dir_all(build_dir.join("bin"));
+    }
 
     // Build bgfx
     let status = Command::new("make")
-                     .env("CFLAGS", cflags)
+                     .env("CFLAGS", &cflags)
                      .arg("-R")
                      .arg("-C")
                      .arg(format!("bgfx/.build/projects/{}", project_name))
@@ -123,7 +153,7 @@ fn build_gmake(bitness: u32, profile: &str, platform: &str) {
 
     // Build bimg
     let status = Command::new("make")
-                     .env("CFLAGS", cflags)
+                     .env("CFLAGS", &cflags)
                      .arg("-R")
                      .arg("-C")
                      .arg(format!("bgfx/.build/projects/{}", project_name))
@@ -137,6 +167,8 @@ fn build_gmake(bitness: u32, profile: &str, platform: &str) {
         panic!("Failed to build bimg.");
     }
 
+    fs::write(&stamp, &cflags).expect("Failed to record the C flags");
+
     // Output linker config
     let mut path = PathBuf::from(env::current_dir().unwrap());
     path.push("bgfx");
@@ -157,6 +189,12 @@ fn build_gmake(bitness: u32, profile: &str, platform: &str) {
             println!("cargo:rustc-link-lib=opengl32");
             println!("cargo:rustc-link-lib=psapi");
         }
+        "unknown-linux" if wayland => {
+            println!("cargo:rustc-link-lib=EGL");
+            println!("cargo:rustc-link-lib=GLESv2");
+            println!("cargo:rustc-link-lib=wayland-egl");
+            println!("cargo:rustc-link-lib=wayland-client");
+        }
         "unknown-linux" => {
             println!("cargo:rustc-link-lib=GL");
             println!("cargo:rustc-link-lib=X11");
@@ -12,13 +12,12 @@ use std::cmp::max;
 
 const LOGO: &'static [u8] = include_bytes!("assets/00-helloworld/logo.bin");
 
-fn example(events: EventQueue) {
+fn example(bgfx: &Bgfx, events: EventQueue) {
     let mut width: u16 = 1280;
     let mut height: u16 = 720;
     let debug = DEBUG_TEXT;
     let reset = RESET_VSYNC;
 
-    let bgfx = bgfx::init(RendererType::Default, None, None).unwrap();
     bgfx.reset(width, height, reset);
 
     // Enable debug text.
@@ -28,7 +27,7 @@ fn example(events: EventQueue) {
     let clear = CLEAR_COLOR | CLEAR_DEPTH;
     bgfx.set_view_clear(0, clear, 0x303030ff, 1.0_f32, 0);
 
-    while !events.handle_events(&bgfx, &mut width, &mut height, reset) {
+    while !common::handle_events(&events, bgfx, &mut width, &mut height, reset) {
         // Set view 0 default viewport.
         bgfx.set_view_rect(0, 0, 0, width, height);
 
@@ -49,8 +48,7 @@ fn example(events: EventQueue) {
         bgfx.frame(false);
     }
 
-    // bgfx will automatically be shut down when the local `bgfx` binding
-    // goes out of scope.
+    // bgfx will automatically be shut down once this function returns.
 }
 
 fn main() {
@@ -2,6 +2,8 @@
 // License: http://opensource.org/licenses/ISC
 
 extern crate bgfx;
+#[macro_use]
+extern crate bgfx_derive;
 extern crate cgmath;
 extern crate glutin;
 extern crate time;
@@ -15,33 +17,25 @@ use common::EventQueue;
 use time::PreciseTime;
 
 
-#[repr(packed)]
+#[derive(Vertex, Copy, Clone)]
+#[repr(C, packed)]
 struct PosColorVertex {
-    _x: f32,
-    _y: f32,
-    _z: f32,
+    #[bgfx(attrib = "Position")]
+    _pos: [f32; 3],
+    #[bgfx(attrib = "Color0", normalized)]
     _abgr: u32,
 }
 
-impl PosColorVertex {
-    fn build_decl() -> VertexDecl {
-        VertexDecl::new(None)
-            .add(Attrib::Position, 3, AttribType::Float)
-            .add(Attrib::Color0, 4, AttribType::Uint8(true))
-            .end()
-    }
-}
-
 #[cfg_attr(rustfmt, rustfmt_skip)]
 static CUBE_VERTICES: [PosColorVertex; 8] = [
-    PosColorVertex { _x: -1.0, _y:  1.0, _z:  1.0, _abgr: 0xff000000 },
-    PosColorVertex { _x:  1.0, _y:  1.0, _z:  1.0, _abgr: 0xff0000ff },
-    PosColorVertex { _x: -1.0, _y: -1.0, _z:  1.0, _abgr: 0xff00ff00 },
-    PosColorVertex { _x:  1.0, _y: -1.0, _z:  1.0, _abgr: 0xff00ffff },
-    PosColorVertex { _x: -1.0, _y:  1.0, _z: -1.0, _abgr: 0xffff0000 },
-    PosColorVertex { _x:  1.0, _y:  1.0, _z: -1.0, _abgr: 0xffff00ff },
-    PosColorVertex { _x: -1.0, _y: -1.0, _z: -1.0, _abgr: 0xffffff00 },
-    PosColorVertex { _x:  1.0, _y: -1.0, _z: -1.0, _abgr: 0xffffffff },
+    PosColorVertex { _pos: [-1.0,  1.0,  1.0], _abgr: 0xff000000 },
+    PosColorVertex { _pos: [ 1.0,  1.0,  1.0], _abgr: 0xff0000ff },
+    PosColorVertex { _pos: [-1.0, -1.0,  1.0], _abgr: 0xff00ff00 },
+    PosColorVertex { _pos: [ 1.0, -1.0,  1.0], _abgr: 0xff00ffff },
+    PosColorVertex { _pos: [-1.0,  1.0, -1.0], _abgr: 0xffff0000 },
+    PosColorVertex { _pos: [ 1.0,  1.0, -1.0], _abgr: 0xffff00ff },
+    PosColorVertex { _pos: [-1.0, -1.0, -1.0], _abgr: 0xffffff00 },
+    PosColorVertex { _pos: [ 1.0, -1.0, -1.0], _abgr: 0xffffffff },
 ];
 
 #[cfg_attr(rustfmt, rustfmt_skip)]
@@ -67,7 +61,7 @@ struct Cubes<'a> {
     height: u16,
     debug: DebugFlags,
     reset: ResetFlags,
-    vbh: Option<VertexBuffer<'a>>,
+    vbh: Option<VertexBuffer<'a, PosColorVertex>>,
     ibh: Option<IndexBuffer<'a>>,
     program: Option<Program<'a>>,
     time: Option<PreciseTime>,
@@ -111,16 +105,11 @@ impl<'a> Cubes<'a> {
         let clear_flags = CLEAR_COLOR | CLEAR_DEPTH;
         self.bgfx.set_view_clear(0, clear_flags, 0x303030ff, 1.0_f32, 0);
 
-        // Create vertex stream declaration
-        let decl = PosColorVertex::build_decl();
-
-        // Create static vertex buffer.
-        self.vbh = Some(VertexBuffer::new(Memory::reference(self.bgfx, &CUBE_VERTICES),
-                                          &decl,
-                                          BUFFER_NONE));
+        // Create static vertex buffer, declared by the vertex type.
+        self.vbh = Some(VertexBuffer::from_slice(self.bgfx, &CUBE_VERTICES, BUFFER_NONE));
 
         // Create static index buffer.
-        self.ibh = Some(IndexBuffer::new(Memory::reference(self.bgfx, &CUBE_INDICES), BUFFER_NONE));
+        self.ibh = Some(IndexBuffer::from_slice(self.bgfx, &CUBE_INDICES, BUFFER_NONE));
 
         // Create program from shaders.
         self.program = Some(common::load_program(&self.bgfx, "vs_cubes", "fs_cubes"));
@@ -135,7 +124,11 @@ impl<'a> Cubes<'a> {
     }
 
     fn update(&mut self) -> bool {
-        if !self.events.handle_events(&self.bgfx, &mut self.width, &mut self.height, self.reset) {
+        if !common::handle_events(&self.events,
+                                  self.bgfx,
+                                  &mut self.width,
+                                  &mut self.height,
+                                  self.reset) {
             let now = PreciseTime::now();
             let frame_time = self.last.unwrap_or(now).to(now);
             self.last = Some(now);
@@ -207,9 +200,8 @@ impl<'a> Cubes<'a> {
     }
 }
 
-fn example(events: EventQueue) {
-    let bgfx = bgfx::init(RendererType::Default, None, None).unwrap();
-    let mut cubes = Cubes::new(&bgfx, events);
+fn example(bgfx: &Bgfx, events: EventQueue) {
+    let mut cubes = Cubes::new(bgfx, events);
     cubes.init();
     while cubes.update() {}
     cubes.shutdown();
@@ -5,7 +5,7 @@ extern crate bgfx;
 extern crate glutin;
 extern crate libc;
 
-use bgfx::{Bgfx, PlatformData, RenderFrame};
+use bgfx::{Bgfx, BgfxError, PlatformData};
 
 use glutin::{Api, GlRequest, Window, WindowBuilder};
 
@@ -13,9 +13,7 @@ use std;
 use std::env;
 use std::fs::File;
 use std::io::Read;
-use std::sync::mpsc::{Receiver, Sender, channel};
-use std::thread;
-use std::time::Duration;
+use std::sync::mpsc::Sender;
 
 /// Events received by the main thread, sent by the render thread.
 #[derive(PartialEq, Eq, Hash, Debug)]
@@ -28,38 +26,32 @@ pub enum Event {
 }
 
 /// Event queue for communicating with the render thread.
-pub struct EventQueue {
-    event_rx: Receiver<Event>,
-}
+pub type EventQueue = bgfx::EventQueue<Event>;
+
+/// Handles events received from the render thread. If there are no events to process, returns
+/// instantly.
+///
+/// Returns `true` if the app should exit.
+pub fn handle_events(events: &EventQueue,
+                     bgfx: &Bgfx,
+                     width: &mut u16,
+                     height: &mut u16,
+                     reset: bgfx::ResetFlags)
+                     -> bool {
+    let mut should_close = false;
 
-impl EventQueue {
-
-    /// Handles events received from the render thread. If there are no events to process, returns
-    /// instantly.
-    ///
-    /// Returns `true` if the app should exit.
-    pub fn handle_events(&self,
-                         bgfx: &Bgfx,
-                         width: &mut u16,
-                         height: &mut u16,
-                         reset: bgfx::ResetFlags)
-                         -> bool {
-        let mut should_close = false;
-
-        while let Ok(result) = self.event_rx.try_recv() {
-            match result {
-                Event::Close => should_close = true,
-                Event::Size(w, h) => {
-                    *width = w;
-                    *height = h;
-                    bgfx.reset(w, h, reset);
-                }
+    while let Some(result) = events.poll() {
+        match result {
+            Event::Close => should_close = true,
+            Event::Size(w, h) => {
+                *width = w;
+                *height = h;
+                bgfx.reset(w, h, reset);
             }
         }
-
-        should_close
     }
 
+    should_close || events.is_closed()
 }
 
 /// Process window events on the render thread.
@@ -100,37 +92,56 @@ pub fn load_program<'a, 'b>(bgfx: &'a Bgfx,
     let exe_path = env::current_exe().unwrap();
     let exe_stem = exe_path.file_stem().unwrap();
     let assets_path = format!("examples/assets/{}", exe_stem.to_str().unwrap());
-    let vsh_path = format!("{}/{:?}/{}.bin", assets_path, renderer, vsh_name);
-    let fsh_path = format!("{}/{:?}/{}.bin", assets_path, renderer, fsh_name);
-    let vsh_mem = bgfx::Memory::copy(bgfx, &load_file(&vsh_path));
-    let fsh_mem = bgfx::Memory::copy(bgfx, &load_file(&fsh_path));
+    let vsh_path = format!("{}/{}/{}.bin", assets_path, renderer, vsh_name);
+    let fsh_path = format!("{}/{}/{}.bin", assets_path, renderer, fsh_name);
+    let vsh_mem = bgfx::Memory::owned(bgfx, load_file(&vsh_path));
+    let fsh_mem = bgfx::Memory::owned(bgfx, load_file(&fsh_path));
     let vsh = bgfx::Shader::new(vsh_mem);
     let fsh = bgfx::Shader::new(fsh_mem);
 
     bgfx::Program::new(vsh, fsh)
 }
 
-/// Set the platform data to be used by BGFX.
-#[cfg(target_os = "linux")]
-fn init_bgfx_platform(window: &Window) {
-    PlatformData::new()
-        .display(unsafe {window.platform_display() as *mut std::os::raw::c_void })
-        .window(unsafe {window.platform_window() as *mut std::os::raw::c_void })
-        .apply()
-        .unwrap();
+/// Window driven by `bgfx::run`.
+struct GlutinPlatform {
+    window: Window,
+    width: u16,
+    height: u16,
 }
 
-/// Set the platform data to be used by BGFX.
-#[cfg(not(target_os = "linux"))]
-fn init_bgfx_platform(window: &Window) {
-    PlatformData::new()
-        .window(unsafe {window.platform_window() as *mut std::os::raw::c_void })
-        .apply()
-        .unwrap();
+impl bgfx::Platform for GlutinPlatform {
+    type Event = Event;
+
+    /// Gets the platform data to be used by BGFX.
+    #[cfg(target_os = "linux")]
+    fn platform_data(&self) -> Result<PlatformData, BgfxError> {
+        let mut data = PlatformData::new();
+        data.display(unsafe { self.window.platform_display() as *mut std::os::raw::c_void })
+            .window(unsafe { self.window.platform_window() as *mut std::os::raw::c_void });
+        Ok(data)
+    }
+
+    /// Gets the platform data to be used by BGFX.
+    #[cfg(not(target_os = "linux"))]
+    fn platform_data(&self) -> Result<PlatformData, BgfxError> {
+        let mut data = PlatformData::new();
+        data.window(unsafe { self.window.platform_window() as *mut std::os::raw::c_void });
+        Ok(data)
+    }
+
+    fn init(&self) -> bgfx::Init {
+        let mut init = bgfx::Init::new();
+        init.resolution(self.width, self.height);
+        init
+    }
+
+    fn pump_events(&mut self, events: &Sender<Event>) -> bool {
+        !process_events(&self.window, events)
+    }
 }
 
 pub fn run_example<M>(width: u16, height: u16, main: M)
-    where M: Send + 'static + FnOnce(EventQueue)
+    where M: Send + 'static + FnOnce(&Bgfx, EventQueue)
 {
     let window = WindowBuilder::new()
                      .with_dimensions(width as u32, height as u32)
@@ -143,29 +154,11 @@ pub fn run_example<M>(width: u16, height: u16, main: M)
         window.make_current().unwrap();
     }
 
-    // Create the channel used for communication between the main and render threads.
-    let (event_tx, event_rx) = channel::<Event>();
-
-    // Set the platform data for BGFX to use.
-    init_bgfx_platform(&window);
-
-    // Initialize this thread as the render thread by pumping it once *before* calling bgfx::init.
-    bgfx::render_frame();
-
-    // Spawn a new thread to use as the main thread.
-    let main_thread = thread::spawn(move || {
-        main(EventQueue { event_rx: event_rx });
-    });
-
-    // Pump window events until the window is closed.
-    while !process_events(&window, &event_tx) {
-        bgfx::render_frame();
-    }
-
-    // Pump the render thread until the main thread has shut down.
-    while bgfx::render_frame() != RenderFrame::NoContext {
-        thread::sleep(Duration::from_millis(1));
-    }
+    let platform = GlutinPlatform {
+        window: window,
+        width: width,
+        height: height,
+    };
 
-    main_thread.join().unwrap();
+    bgfx::run(platform, main).expect("Failed to run example");
 }
@@ -89,61 +89,73 @@ pub fn state_point_size(size: u8) -> StateFlags {
 }
 
 #[inline]
+#[deprecated(note = "use `RenderState::blend` instead")]
 pub fn state_blend_func_separate(srcrgb: u32, dstrgb: u32, srca: u8, dsta: u8) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_SEPARATE!(srcrgb, dstrgb, srca, dsta)).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `RenderState::blend` instead")]
 pub fn state_blend_equation_separate(rgb: u32, a: u8) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_EQUATION_SEPARATE!(rgb, a)).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `RenderState::blend` instead")]
 pub fn state_blend_func(src: u32, dst: u32) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_SEPARATE!(src, dst, src, dst)).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `RenderState::blend` instead")]
 pub fn state_blend_equation(equation: u32) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_EQUATION_SEPARATE!(equation, equation)).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_x(src: u32, dst: u32) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_x!(src, dst) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_xe(src: u32, dst: u32, equation: u64) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_xE!(src, dst, equation) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_1(src: u32, dst: u32) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_1!(src, dst) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_2(src: u32, dst: u32) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_2!(src, dst) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_3(src: u32, dst: u32) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_3!(src, dst) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_1e(src: u32, dst: u32, equation: u64) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_1E!(src, dst, equation) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_2e(src: u32, dst: u32, equation: u64) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_2E!(src, dst, equation) as u64).unwrap()
 }
 
 #[inline]
+#[deprecated(note = "use `IndependentBlend` instead")]
 pub fn state_blend_func_rt_3e(src: u32, dst: u32, equation: u64) -> StateFlags {
     StateFlags::from_bits(BGFX_STATE_BLEND_FUNC_RT_3E!(src, dst, equation) as u64).unwrap()
 }
@@ -228,6 +240,21 @@ impl Default for DebugFlags {
     }
 }
 
+bitflags! {
+    flags AttribFlags: u8 {
+        const ATTRIB_NONE = 0x00,
+        const ATTRIB_NORMALIZED = 0x01,
+        const ATTRIB_AS_INT = 0x02,
+    }
+}
+
+impl Default for AttribFlags {
+    #[inline]
+    fn default() -> AttribFlags {
+        ATTRIB_NONE
+    }
+}
+
 bitflags! {
     flags ResetFlags: u32 {
         const RESET_NONE = bgfx_sys::BGFX_RESET_NONE,
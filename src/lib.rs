@@ -28,65 +28,132 @@
 //!     .expect("Could not set platform data");
 //! ```
 //!
-//! Once the platform data has been initialized, a new thread should be spawned to act as the main
-//! thread. This thread should call [`bgfx::init`] to initialize bgfx. The object returned by that
-//! function should be used to access bgfx API calls.
+//! Once the platform data has been initialized, the real main thread should claim the
+//! [`RenderThread`] token, so that it acts as the render thread. This has to happen *before* bgfx
+//! is initialized, or bgfx creates its own render thread.
+//!
+//! Then a new thread should be spawned to act as the main thread. This thread should use
+//! [`bgfx::Init`] to initialize bgfx. The object returned by that initializer should be used to
+//! access bgfx API calls. Meanwhile, the render thread repeatedly calls
+//! [`RenderThread::render_frame`].
 //!
 //! ```no_run
+//! let render_thread = bgfx::RenderThread::claim().expect("Render thread already claimed");
+//!
 //! std::thread::spawn(|| {
-//!     let bgfx = bgfx::init(bgfx::RendererType::Default, None, None)
+//!     let bgfx = bgfx::Init::new()
+//!         .init()
 //!         .expect("Failed to initialize bgfx");
 //!     // ...
 //! });
-//! ```
-//!
-//! Finally, the real main thread should act as the render thread, and repeatedly be calling
-//! [`bgfx::render_frame`].
 //!
-//! ```no_run
 //! loop {
 //!     // This is probably also where you will want to pump the window event queue.
-//!     bgfx::render_frame();
+//!     render_thread.render_frame();
 //! }
 //! ```
 //!
+//! Alternatively, bgfx can run entirely on one thread by initializing it in
+//! [single-threaded][`Init::single_threaded`] mode, in which case the thread that initialized bgfx
+//! also renders the frames.
+//!
+//! Rather than setting this up manually, [`bgfx::run`] can be used to drive any windowing system
+//! implementing [`Platform`] this way.
+//!
 //! See the examples for more in-depth usage.
 //!
+//! ## Headless Usage
+//!
+//! For testing and tooling, bgfx can be initialized with the `Noop` renderer through
+//! [`Init::headless`]. This needs neither platform data nor a window, and since no render thread
+//! is claimed, bgfx spawns its own render thread. The API can then be used from a single thread,
+//! without pumping a render loop, and headless instances can be initialized from any thread, one
+//! at a time.
+//!
+//! ```no_run
+//! let bgfx = bgfx::Init::headless().init().expect("Failed to initialize bgfx");
+//! bgfx.touch(0);
+//! bgfx.frame(false);
+//! ```
+//!
 //! [bgfx]: https://github.com/bkaradzic/bgfx
 //! [bgfx building]: https://bkaradzic.github.io/bgfx/build.html
-//! [`bgfx::init`]: fn.init.html
-//! [`bgfx::render_frame`]: fn.render_frame.html
+//! [`bgfx::Init`]: struct.Init.html
+//! [`Init::headless`]: struct.Init.html#method.headless
+//! [`Init::single_threaded`]: struct.Init.html#method.single_threaded
+//! [`RenderThread`]: struct.RenderThread.html
+//! [`RenderThread::render_frame`]: struct.RenderThread.html#method.render_frame
 //! [`PlatformData`]: struct.PlatformData.html
+//! [`bgfx::run`]: runner/fn.run.html
+//! [`Platform`]: runner/trait.Platform.html
 
+#[cfg(feature = "derive")]
+extern crate bgfx_derive;
 #[macro_use]
 extern crate bgfx_sys;
 #[macro_use]
 extern crate bitflags;
 extern crate libc;
+extern crate raw_window_handle;
 
+use std::cell::RefCell;
+use std::error;
 use std::ffi;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::{Deref, Range};
 use std::ptr;
+use std::rc::Rc;
+use std::str;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, ThreadId};
+
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle,
+                        RawWindowHandle};
 
 pub mod flags;
+pub mod runner;
+pub mod state;
+pub mod topology;
+#[cfg(all(feature = "wayland", target_os = "linux"))]
+mod wayland;
 
 pub use flags::*;
+pub use runner::*;
+pub use state::*;
+
+#[cfg(feature = "derive")]
+pub use bgfx_derive::Vertex;
+
+/// GPU vendor, used to select which adapter bgfx should initialize on.
+#[repr(u16)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Vendor {
+    /// Autoselect adapter.
+    None = bgfx_sys::BGFX_PCI_ID_NONE,
 
-/// Autoselect adapter.
-pub const PCI_ID_NONE: u16 = bgfx_sys::BGFX_PCI_ID_NONE;
+    /// Software rasterizer.
+    SoftwareRasterizer = bgfx_sys::BGFX_PCI_ID_SOFTWARE_RASTERIZER,
 
-/// Software rasterizer.
-pub const PCI_ID_SOFTWARE_RASTERIZER: u16 = bgfx_sys::BGFX_PCI_ID_SOFTWARE_RASTERIZER;
+    /// AMD adapter.
+    Amd = bgfx_sys::BGFX_PCI_ID_AMD,
 
-/// AMD adapter.
-pub const PCI_ID_AMD: u16 = bgfx_sys::BGFX_PCI_ID_AMD;
+    /// Intel adapter.
+    Intel = bgfx_sys::BGFX_PCI_ID_INTEL,
 
-/// Intel adapter.
-pub const PCI_ID_INTEL: u16 = bgfx_sys::BGFX_PCI_ID_INTEL;
+    /// nVidia adapter.
+    Nvidia = bgfx_sys::BGFX_PCI_ID_NVIDIA,
+}
 
-/// nVidia adapter.
-pub const PCI_ID_NVIDIA: u16 = bgfx_sys::BGFX_PCI_ID_NVIDIA;
+impl Default for Vendor {
+    #[inline]
+    fn default() -> Vendor {
+        Vendor::None
+    }
+}
 
 /// Renderer backend type.
 #[repr(u32)]
@@ -123,7 +190,95 @@ pub enum RendererType {
     Default = bgfx_sys::bgfx_renderer_type_BGFX_RENDERER_TYPE_COUNT as u32,
 }
 
-/// `render_frame()` results.
+impl RendererType {
+
+    /// Gets the human-readable name bgfx uses for this renderer, such as `"Direct3D 11"`.
+    ///
+    /// Can be called before bgfx has been initialized.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        if *self == RendererType::Default {
+            return "Default";
+        }
+
+        unsafe {
+            let name = bgfx_sys::bgfx_get_renderer_name(mem::transmute(*self));
+            str::from_utf8(ffi::CStr::from_ptr(name).to_bytes()).unwrap()
+        }
+    }
+
+    /// Gets the identifier used by the `Display` and `FromStr` implementations, which matches
+    /// the variant name.
+    fn ident(&self) -> &'static str {
+        match *self {
+            RendererType::Noop => "Noop",
+            RendererType::Direct3D9 => "Direct3D9",
+            RendererType::Direct3D11 => "Direct3D11",
+            RendererType::Direct3D12 => "Direct3D12",
+            RendererType::GNM => "GNM",
+            RendererType::Metal => "Metal",
+            RendererType::OpenGLES => "OpenGLES",
+            RendererType::OpenGL => "OpenGL",
+            RendererType::Vulkan => "Vulkan",
+            RendererType::Default => "Default",
+        }
+    }
+
+}
+
+impl fmt::Display for RendererType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.ident())
+    }
+}
+
+impl str::FromStr for RendererType {
+    type Err = ParseRendererTypeError;
+
+    /// Parses a renderer from its variant name, ignoring case. `"vulkan"` and `"Vulkan"` both
+    /// parse as `RendererType::Vulkan`.
+    fn from_str(s: &str) -> Result<RendererType, ParseRendererTypeError> {
+        let renderers = [RendererType::Noop,
+                         RendererType::Direct3D9,
+                         RendererType::Direct3D11,
+                         RendererType::Direct3D12,
+                         RendererType::GNM,
+                         RendererType::Metal,
+                         RendererType::OpenGLES,
+                         RendererType::OpenGL,
+                         RendererType::Vulkan,
+                         RendererType::Default];
+
+        renderers.iter()
+                 .find(|r| r.ident().eq_ignore_ascii_case(s))
+                 .cloned()
+                 .ok_or_else(|| ParseRendererTypeError { name: s.to_owned() })
+    }
+}
+
+/// Error returned when parsing an unknown renderer name into a [`RendererType`].
+///
+/// [`RendererType`]: enum.RendererType.html
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ParseRendererTypeError {
+    name: String,
+}
+
+impl fmt::Display for ParseRendererTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown renderer: {:?}", self.name)
+    }
+}
+
+impl error::Error for ParseRendererTypeError {
+    fn description(&self) -> &str {
+        "unknown renderer"
+    }
+}
+
+/// [`RenderThread::render_frame`] results.
+///
+/// [`RenderThread::render_frame`]: struct.RenderThread.html#method.render_frame
 #[repr(u32)]
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum RenderFrame {
@@ -194,43 +349,289 @@ pub enum Attrib {
 }
 
 /// Vertex attribute type.
+///
+/// Whether the values are normalized, or passed to the shader as integers, is set separately
+/// through [`VertexDeclBuilder::add`].
+///
+/// [`VertexDeclBuilder::add`]: struct.VertexDeclBuilder.html#method.add
+#[repr(u32)]
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub enum AttribType {
     /// Unsigned 8-bit integer.
-    ///
-    /// If the parameter is `true`, the value will be normalized between 0 and 1.
-    Uint8(bool),
+    Uint8 = bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_UINT8 as u32,
 
-    /// Signed 8-bit integer.
-    ///
-    /// If the parameter is `true`, the value will be normalized between 0 and 1.
-    Int8(bool),
+    /// Unsigned 10-bit integer, packed into 32 bits along with the other components. Not
+    /// supported by all renderers.
+    Uint10 = bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_UINT10 as u32,
 
-    /// Unsigned 10-bit integer.
-    ///
-    /// If the parameter is `true`, the value will be normalized between 0 and 1.
-    Uint10(bool),
+    /// Signed 16-bit integer.
+    Int16 = bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_INT16 as u32,
 
-    /// Signed 10-bit integer.
-    ///
-    /// If the parameter is `true`, the value will be normalized between 0 and 1.
-    Int10(bool),
+    /// 16-bit float. Not supported by all renderers.
+    Half = bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_HALF as u32,
 
-    /// Unsigned 16-bit integer.
-    ///
-    /// If the parameter is `true`, the value will be normalized between 0 and 1.
-    Uint16(bool),
+    /// 32-bit float.
+    Float = bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_FLOAT as u32,
+}
 
-    /// Signed 16-bit integer.
+/// Texture format.
+#[repr(u32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TextureFormat {
+    /// BC1 block compression (DXT1).
+    Bc1 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC1 as u32,
+
+    /// BC2 block compression (DXT3).
+    Bc2 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC2 as u32,
+
+    /// BC3 block compression (DXT5).
+    Bc3 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC3 as u32,
+
+    /// BC4 block compression (LATC1/ATI1).
+    Bc4 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC4 as u32,
+
+    /// BC5 block compression (LATC2/ATI2).
+    Bc5 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC5 as u32,
+
+    /// BC6H block compression.
+    Bc6h = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC6H as u32,
+
+    /// BC7 block compression.
+    Bc7 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BC7 as u32,
+
+    /// ETC1 RGB8.
+    Etc1 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_ETC1 as u32,
+
+    /// ETC2 RGB8.
+    Etc2 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_ETC2 as u32,
+
+    /// ETC2 RGBA8.
+    Etc2a = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_ETC2A as u32,
+
+    /// ETC2 RGB8A1.
+    Etc2a1 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_ETC2A1 as u32,
+
+    /// PVRTC1 RGB 2 bits per pixel.
+    Ptc12 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_PTC12 as u32,
+
+    /// PVRTC1 RGB 4 bits per pixel.
+    Ptc14 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_PTC14 as u32,
+
+    /// PVRTC1 RGBA 2 bits per pixel.
+    Ptc12a = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_PTC12A as u32,
+
+    /// PVRTC1 RGBA 4 bits per pixel.
+    Ptc14a = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_PTC14A as u32,
+
+    /// PVRTC2 RGBA 2 bits per pixel.
+    Ptc22 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_PTC22 as u32,
+
+    /// PVRTC2 RGBA 4 bits per pixel.
+    Ptc24 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_PTC24 as u32,
+
+    /// 1-bit single channel.
+    R1 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R1 as u32,
+
+    /// 8-bit alpha only.
+    A8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_A8 as u32,
+
+    /// 8-bit R, unsigned normalized.
+    R8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R8 as u32,
+
+    /// 8-bit R, signed integer.
+    R8i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R8I as u32,
+
+    /// 8-bit R, unsigned integer.
+    R8u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R8U as u32,
+
+    /// 8-bit R, signed normalized.
+    R8s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R8S as u32,
+
+    /// 16-bit R, unsigned normalized.
+    R16 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R16 as u32,
+
+    /// 16-bit R, signed integer.
+    R16i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R16I as u32,
+
+    /// 16-bit R, unsigned integer.
+    R16u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R16U as u32,
+
+    /// 16-bit R, float.
+    R16f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R16F as u32,
+
+    /// 16-bit R, signed normalized.
+    R16s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R16S as u32,
+
+    /// 32-bit R, signed integer.
+    R32i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R32I as u32,
+
+    /// 32-bit R, unsigned integer.
+    R32u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R32U as u32,
+
+    /// 32-bit R, float.
+    R32f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R32F as u32,
+
+    /// 8-bit RG, unsigned normalized.
+    Rg8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG8 as u32,
+
+    /// 8-bit RG, signed integer.
+    Rg8i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG8I as u32,
+
+    /// 8-bit RG, unsigned integer.
+    Rg8u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG8U as u32,
+
+    /// 8-bit RG, signed normalized.
+    Rg8s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG8S as u32,
+
+    /// 16-bit RG, unsigned normalized.
+    Rg16 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG16 as u32,
+
+    /// 16-bit RG, signed integer.
+    Rg16i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG16I as u32,
+
+    /// 16-bit RG, unsigned integer.
+    Rg16u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG16U as u32,
+
+    /// 16-bit RG, float.
+    Rg16f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG16F as u32,
+
+    /// 16-bit RG, signed normalized.
+    Rg16s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG16S as u32,
+
+    /// 32-bit RG, signed integer.
+    Rg32i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG32I as u32,
+
+    /// 32-bit RG, unsigned integer.
+    Rg32u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG32U as u32,
+
+    /// 32-bit RG, float.
+    Rg32f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG32F as u32,
+
+    /// 8-bit RGB, unsigned normalized.
+    Rgb8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB8 as u32,
+
+    /// 8-bit RGB, signed integer.
+    Rgb8i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB8I as u32,
+
+    /// 8-bit RGB, unsigned integer.
+    Rgb8u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB8U as u32,
+
+    /// 8-bit RGB, signed normalized.
+    Rgb8s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB8S as u32,
+
+    /// Shared exponent RGB float.
+    Rgb9e5f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB9E5F as u32,
+
+    /// 8-bit BGRA, unsigned normalized.
+    Bgra8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_BGRA8 as u32,
+
+    /// 8-bit RGBA, unsigned normalized.
+    Rgba8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA8 as u32,
+
+    /// 8-bit RGBA, signed integer.
+    Rgba8i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA8I as u32,
+
+    /// 8-bit RGBA, unsigned integer.
+    Rgba8u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA8U as u32,
+
+    /// 8-bit RGBA, signed normalized.
+    Rgba8s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA8S as u32,
+
+    /// 16-bit RGBA, unsigned normalized.
+    Rgba16 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA16 as u32,
+
+    /// 16-bit RGBA, signed integer.
+    Rgba16i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA16I as u32,
+
+    /// 16-bit RGBA, unsigned integer.
+    Rgba16u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA16U as u32,
+
+    /// 16-bit RGBA, float.
+    Rgba16f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA16F as u32,
+
+    /// 16-bit RGBA, signed normalized.
+    Rgba16s = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA16S as u32,
+
+    /// 32-bit RGBA, signed integer.
+    Rgba32i = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA32I as u32,
+
+    /// 32-bit RGBA, unsigned integer.
+    Rgba32u = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA32U as u32,
+
+    /// 32-bit RGBA, float.
+    Rgba32f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA32F as u32,
+
+    /// Packed 5-6-5 RGB, unsigned normalized.
+    R5g6b5 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_R5G6B5 as u32,
+
+    /// 4-bit RGBA, unsigned normalized.
+    Rgba4 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGBA4 as u32,
+
+    /// Packed 5-5-5-1 RGBA, unsigned normalized.
+    Rgb5a1 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB5A1 as u32,
+
+    /// Packed 10-10-10-2 RGBA, unsigned normalized.
+    Rgb10a2 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RGB10A2 as u32,
+
+    /// Packed 11-11-10 RGB float.
+    Rg11b10f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_RG11B10F as u32,
+
+    /// 16-bit depth.
+    D16 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D16 as u32,
+
+    /// 24-bit depth.
+    D24 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D24 as u32,
+
+    /// 24-bit depth and 8-bit stencil.
+    D24s8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D24S8 as u32,
+
+    /// 32-bit depth.
+    D32 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D32 as u32,
+
+    /// 16-bit float depth.
+    D16f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D16F as u32,
+
+    /// 24-bit float depth.
+    D24f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D24F as u32,
+
+    /// 32-bit float depth.
+    D32f = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D32F as u32,
+
+    /// 8-bit stencil only.
+    D0s8 = bgfx_sys::bgfx_texture_format::BGFX_TEXTURE_FORMAT_D0S8 as u32,
+}
+
+/// Uniform type.
+#[repr(u32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum UniformType {
+    /// Texture sampler. Set through [`Draw::texture`].
     ///
-    /// If the parameter is `true`, the value will be normalized between 0 and 1.
-    Int16(bool),
+    /// [`Draw::texture`]: struct.Draw.html#method.texture
+    Sampler = bgfx_sys::bgfx_uniform_type::BGFX_UNIFORM_TYPE_INT1 as u32,
 
-    /// 16-bit float.
-    Half,
+    /// 4 component vector.
+    Vec4 = bgfx_sys::bgfx_uniform_type::BGFX_UNIFORM_TYPE_VEC4 as u32,
+
+    /// 3x3 matrix.
+    Mat3 = bgfx_sys::bgfx_uniform_type::BGFX_UNIFORM_TYPE_MAT3 as u32,
+
+    /// 4x4 matrix.
+    Mat4 = bgfx_sys::bgfx_uniform_type::BGFX_UNIFORM_TYPE_MAT4 as u32,
+}
+
+impl UniformType {
+
+    /// Number of floats making up one element of the uniform.
+    fn components(self) -> usize {
+        match self {
+            UniformType::Sampler => 1,
+            UniformType::Vec4 => 4,
+            UniformType::Mat3 => 9,
+            UniformType::Mat4 => 16,
+        }
+    }
 
-    /// 32-bit float.
-    Float,
 }
 
 /// bgfx error.
@@ -242,210 +643,934 @@ pub enum BgfxError {
     /// An invalid window was provided in the platform data.
     InvalidWindow,
 
+    /// The window handle is of a kind bgfx does not support.
+    UnsupportedWindowHandle,
+
+    /// Wayland platform data was provided, but the crate was built without the `wayland` feature.
+    WaylandUnsupported,
+
+    /// The requested renderer is not supported on this platform.
+    UnsupportedRenderer(RendererType),
+
     /// Initialization failed.
     InitFailed,
+
+    /// bgfx has already been initialized, and not yet shut down.
+    AlreadyInitialized,
+
+    /// The render thread has already been claimed, by this or another thread.
+    RenderThreadClaimed,
+
+    /// A draw was submitted without a vertex buffer.
+    MissingVertices,
+
+    /// A vertex or index range reaches past the end of its buffer.
+    RangeOutOfBounds,
+
+    /// A uniform value does not match the type or number of elements of its uniform.
+    UniformMismatch,
+
+    /// The texture data does not match the size of the texture.
+    TextureSizeMismatch,
 }
 
+/// Plain data, which can be handed to bgfx as raw bytes.
+///
+/// # Safety
+///
+/// Implementors must be made up of `Pod` fields only, with no padding between or after them.
+/// This rules out pointers and references, and since the type is `Copy`, it can not implement
+/// `Drop`. Deriving [`Vertex`] implements this after checking that the struct has no padding.
+///
+/// [`Vertex`]: trait.Vertex.html
+pub unsafe trait Pod: Copy + 'static {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for i8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for i16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl Pod for u64 {}
+unsafe impl Pod for i64 {}
+unsafe impl Pod for f32 {}
+unsafe impl Pod for f64 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
 /// bgfx-managed buffer of memory.
 ///
-/// It can be created by either copying existing data through [`copy(...)`], or by referencing
-/// existing memory directly through [`reference(...)`].
+/// Memory borrows the [`Bgfx`] object it was created with, as do all resources created from it.
+/// This guarantees that every resource is destroyed before bgfx is shut down.
+///
+/// Resources are only borrowed for the duration of the calls that use them, not for the rest of
+/// the frame. Dropping a resource instead queues it for destruction after the next
+/// [`Bgfx::frame`], which keeps it alive for any draws that were already set up or submitted.
+///
+/// ```compile_fail
+/// let ib = {
+///     let bgfx = bgfx::Init::headless().init().unwrap();
+///     bgfx::IndexBuffer::new(bgfx::Memory::reference(&bgfx, &[0u16, 1, 2]), bgfx::BUFFER_NONE)
+/// };
+/// ```
+///
+/// It can be created by either copying existing data through [`copy(...)`], by handing owned data
+/// over to bgfx through [`owned(...)`], or by referencing static memory directly through
+/// [`reference(...)`].
 ///
+/// The memory is only handed over to bgfx once it is passed into a bgfx call, which consumes it.
+/// If it is dropped before that, any data it owns is released right away.
+///
+/// [`Bgfx`]: struct.Bgfx.html
+/// [`Bgfx::frame`]: struct.Bgfx.html#method.frame
 /// [`copy(...)`]: #method.copy
+/// [`owned(...)`]: #method.owned
 /// [`reference(...)`]: #method.reference
 pub struct Memory<'b> {
-    handle: *const bgfx_sys::bgfx_memory_t,
-    _phantom: PhantomData<&'b ()>,
+    data: *const std::os::raw::c_void,
+    size: u32,
+    release: bgfx_sys::bgfx_release_fn_t,
+    user_data: *mut std::os::raw::c_void,
+    bgfx: &'b Bgfx,
 }
 
 impl<'b> Memory<'b> {
 
     /// Copies the source data into a new bgfx-managed buffer.
     ///
-    /// **IMPORTANT:** If this buffer is never passed into a bgfx call, the memory will never be
-    /// freed, and will leak.
+    /// # Panics
+    ///
+    /// Panics if the data is 4 GiB or larger.
     #[inline]
-    pub fn copy<'d, T>(_bgfx: &'b Bgfx, data: &'d [T]) -> Memory<'b> {
-        unsafe {
-            let handle = bgfx_sys::bgfx_copy(data.as_ptr() as *const std::os::raw::c_void,
-                                             mem::size_of_val(data) as u32);
-            Memory { handle: handle, _phantom: PhantomData }
+    pub fn copy<'d, T: Pod>(bgfx: &'b Bgfx, data: &'d [T]) -> Memory<'b> {
+        // Checked up front, rather than after copying all of it.
+        memory_size(data);
+
+        let bytes = unsafe {
+            std::slice::from_raw_parts(data.as_ptr() as *const u8, mem::size_of_val(data))
+        };
+        Memory::owned(bgfx, bytes.to_vec())
+    }
+
+    /// Hands owned data, such as a `Vec<u8>`, `Box<[T]>` or `Arc<[u8]>`, over to bgfx without
+    /// copying it. bgfx reads the data directly, and drops it once it has finished using it.
+    ///
+    /// The data may be dropped on the render thread, which is why it has to be `Send`. Its elements
+    /// are read as raw bytes, which is why they have to be [`Pod`]:
+    ///
+    /// ```compile_fail
+    /// let bgfx = bgfx::Init::headless().init().unwrap();
+    /// bgfx::Memory::owned(&bgfx, vec![String::from("not plain data")]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data is 4 GiB or larger.
+    ///
+    /// [`Pod`]: trait.Pod.html
+    #[inline]
+    pub fn owned<T, D>(bgfx: &'b Bgfx, data: D) -> Memory<'b>
+        where T: Pod,
+              D: AsRef<[T]> + Send + 'static
+    {
+        // Boxing the data makes sure it stays put until the release callback drops it.
+        let data = Box::new(data);
+        let (ptr, size) = {
+            let slice = (*data).as_ref();
+            (slice.as_ptr() as *const std::os::raw::c_void, memory_size(slice))
+        };
+
+        Memory {
+            data: ptr,
+            size: size,
+            release: Some(release_owned::<D>),
+            user_data: Box::into_raw(data) as *mut _,
+            bgfx: bgfx,
         }
     }
 
     /// Creates a reference to the source data for passing into bgfx. When using this constructor
     /// over the `copy` call, no copy will be created. bgfx will read the source memory directly.
     ///
-    /// *Note that this is only valid for memory that will live for longer than the bgfx object,
-    /// as it's the only way we can guarantee that the memory will still be valid until bgfx has
-    /// finished using it.*
+    /// Only static data can be referenced, as bgfx may read it at any point until it has been
+    /// rendered. Use [`owned(...)`] to hand over data that is not static.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data is 4 GiB or larger.
+    ///
+    /// [`owned(...)`]: #method.owned
     #[inline]
-    pub fn reference<T>(_bgfx: &'b Bgfx, data: &'b [T]) -> Memory<'b> {
-        // TODO: The lifetime setup probably isn't 100% complete. Need to figure out how we can
-        // guarantee that `data` will outlast `_bgfx`.
-        unsafe {
-            let handle = bgfx_sys::bgfx_make_ref(data.as_ptr() as *const std::os::raw::c_void,
-                                                 mem::size_of_val(data) as u32);
-            Memory { handle: handle, _phantom: PhantomData }
+    pub fn reference<T: Pod>(bgfx: &'b Bgfx, data: &'static [T]) -> Memory<'b> {
+        Memory {
+            data: data.as_ptr() as *const std::os::raw::c_void,
+            size: memory_size(data),
+            release: None,
+            user_data: ptr::null_mut(),
+            bgfx: bgfx,
+        }
+    }
+
+    /// Hands the memory over to bgfx, which becomes responsible for releasing it. The returned
+    /// pointer must be passed into a bgfx call right away.
+    #[inline]
+    fn into_raw(self) -> *const bgfx_sys::bgfx_memory_t {
+        let handle = unsafe {
+            bgfx_sys::bgfx_make_ref_release(self.data, self.size, self.release, self.user_data)
+        };
+        mem::forget(self);
+        handle
+    }
+
+}
+
+impl<'b> Drop for Memory<'b> {
+
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self.data as *mut _, self.user_data) }
         }
     }
 
 }
 
+/// Gets the size of the data in bytes, as bgfx takes it.
+///
+/// # Panics
+///
+/// Panics if the data is 4 GiB or larger, which bgfx can not address.
+fn memory_size<T>(data: &[T]) -> u32 {
+    let size = mem::size_of_val(data);
+    assert!(size <= std::u32::MAX as usize, "memory of {} bytes is too large for bgfx", size);
+    size as u32
+}
+
+/// Release callback for memory created through `Memory::owned`, dropping the boxed data.
+unsafe extern "C" fn release_owned<D>(_ptr: *mut std::os::raw::c_void,
+                                      user_data: *mut std::os::raw::c_void) {
+    drop(Box::from_raw(user_data as *mut D));
+}
+
 /// Shader program.
 ///
 /// The program holds a vertex shader and a fragment shader.
-pub struct Program<'s> {
+pub struct Program<'b> {
     handle: bgfx_sys::bgfx_program_handle_t,
-    _vsh: Shader<'s>,
-    _fsh: Shader<'s>,
+    bgfx: &'b Bgfx,
+    _vsh: Shader<'b>,
+    _fsh: Shader<'b>,
 }
 
-impl<'s> Program<'s> {
+impl<'b> Program<'b> {
 
     /// Creates a new program from a vertex shader and a fragment shader. Ownerships of the shaders
     /// are moved to the program.
     #[inline]
-    pub fn new(vsh: Shader<'s>, fsh: Shader<'s>) -> Program<'s> {
+    pub fn new(vsh: Shader<'b>, fsh: Shader<'b>) -> Program<'b> {
         unsafe {
             let handle = bgfx_sys::bgfx_create_program(vsh.handle, fsh.handle, false);
-            Program { handle: handle, _vsh: vsh, _fsh: fsh }
+            Program { handle: handle, bgfx: vsh.bgfx, _vsh: vsh, _fsh: fsh }
         }
     }
 
 }
 
-impl<'s> Drop for Program<'s> {
+impl<'b> Drop for Program<'b> {
 
     #[inline]
     fn drop(&mut self) {
-        unsafe { bgfx_sys::bgfx_destroy_program(self.handle) }
+        self.bgfx.destroy(Destroy::Program(self.handle));
     }
 
 }
 
 /// Shader.
-pub struct Shader<'m> {
+pub struct Shader<'b> {
     handle: bgfx_sys::bgfx_shader_handle_t,
-    _phantom: PhantomData<&'m ()>,
+    bgfx: &'b Bgfx,
 }
 
-impl<'m> Shader<'m> {
+impl<'b> Shader<'b> {
 
     /// Creates a new shader from bgfx-managed memory.
     #[inline]
-    pub fn new(data: Memory<'m>) -> Shader<'m> {
+    pub fn new(data: Memory<'b>) -> Shader<'b> {
+        let bgfx = data.bgfx;
         unsafe {
-            let handle = bgfx_sys::bgfx_create_shader(data.handle);
-            Shader { handle: handle, _phantom: PhantomData }
+            let handle = bgfx_sys::bgfx_create_shader(data.into_raw());
+            Shader { handle: handle, bgfx: bgfx }
         }
     }
 
 }
 
-impl<'m> Drop for Shader<'m> {
+impl<'b> Drop for Shader<'b> {
 
     #[inline]
     fn drop(&mut self) {
-        unsafe { bgfx_sys::bgfx_destroy_shader(self.handle) }
+        self.bgfx.destroy(Destroy::Shader(self.handle));
     }
 
 }
 
 /// Vertex index buffer.
-pub struct IndexBuffer<'m> {
+pub struct IndexBuffer<'b> {
     handle: bgfx_sys::bgfx_index_buffer_handle_t,
-    _phantom: PhantomData<&'m ()>,
+    bgfx: &'b Bgfx,
+    num: u32,
 }
 
-impl<'m> IndexBuffer<'m> {
+impl<'b> IndexBuffer<'b> {
 
     /// Creates a new index buffer from bgfx-managed memory.
+    ///
+    /// The indices are 16-bit, unless `BUFFER_INDEX32` is set.
     #[inline]
-    pub fn new(indices: Memory<'m>, flags: BufferFlags) -> IndexBuffer<'m> {
+    pub fn new(indices: Memory<'b>, flags: BufferFlags) -> IndexBuffer<'b> {
+        let bgfx = indices.bgfx;
+        let index_size = if flags.contains(BUFFER_INDEX32) { 4 } else { 2 };
+        let num = indices.size / index_size;
         unsafe {
-            let handle = bgfx_sys::bgfx_create_index_buffer(indices.handle, flags.bits());
-            IndexBuffer { handle: handle, _phantom: PhantomData }
+            let handle = bgfx_sys::bgfx_create_index_buffer(indices.into_raw(), flags.bits());
+            IndexBuffer { handle: handle, bgfx: bgfx, num: num }
         }
     }
 
+    /// Creates a new index buffer from a copy of the given indices. `BUFFER_INDEX32` is set
+    /// automatically for `u32` indices, and cleared for `u16` indices.
+    #[inline]
+    pub fn from_slice<I: Index>(bgfx: &'b Bgfx,
+                                indices: &[I],
+                                flags: BufferFlags)
+                                -> IndexBuffer<'b> {
+        IndexBuffer::new(Memory::copy(bgfx, indices), (flags - BUFFER_INDEX32) | I::flags())
+    }
+
+    /// Gets the number of indices in the buffer.
+    #[inline]
+    pub fn num_indices(&self) -> u32 {
+        self.num
+    }
+
 }
 
-impl<'m> Drop for IndexBuffer<'m> {
+impl<'b> Drop for IndexBuffer<'b> {
 
     #[inline]
     fn drop(&mut self) {
-        unsafe { bgfx_sys::bgfx_destroy_index_buffer(self.handle) }
+        self.bgfx.destroy(Destroy::IndexBuffer(self.handle));
+    }
+
+}
+
+/// Index type of an [`IndexBuffer`]. Implemented for `u16` and `u32`.
+///
+/// [`IndexBuffer`]: struct.IndexBuffer.html
+pub trait Index: Pod + Into<u32> + sealed::Sealed {
+    /// Gets the buffer flags required for indices of this type.
+    fn flags() -> BufferFlags;
+}
+
+impl Index for u16 {
+    #[inline]
+    fn flags() -> BufferFlags {
+        BUFFER_NONE
+    }
+}
+
+impl Index for u32 {
+    #[inline]
+    fn flags() -> BufferFlags {
+        BUFFER_INDEX32
     }
+}
 
+mod sealed {
+    /// Keeps `Index` from being implemented for types bgfx does not support.
+    pub trait Sealed {}
+
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
 }
 
 /// Vertex data buffer.
-pub struct VertexBuffer<'m> {
+///
+/// Buffers created through [`from_slice`] are typed by their vertex type `V`. Buffers created
+/// from raw memory through [`new`] are untyped.
+///
+/// [`from_slice`]: #method.from_slice
+/// [`new`]: #method.new
+pub struct VertexBuffer<'b, V = ()> {
     handle: bgfx_sys::bgfx_vertex_buffer_handle_t,
-    _phantom: PhantomData<&'m Bgfx>,
+    bgfx: &'b Bgfx,
+    num: u32,
+    _vertex: PhantomData<fn() -> V>,
 }
 
-impl<'m> VertexBuffer<'m> {
+impl<'b> VertexBuffer<'b> {
 
     /// Creates a new vertex buffer from bgfx-managed memory.
     #[inline]
-    pub fn new<'v>(verts: Memory<'m>,
+    pub fn new<'v>(verts: Memory<'b>,
                    decl: &'v VertexDecl,
                    flags: BufferFlags)
-                   -> VertexBuffer<'m> {
+                   -> VertexBuffer<'b> {
+        let bgfx = verts.bgfx;
+        let num = verts.size.checked_div(decl.decl.stride as u32).unwrap_or(0);
         unsafe {
-            let handle = bgfx_sys::bgfx_create_vertex_buffer(verts.handle,
+            let handle = bgfx_sys::bgfx_create_vertex_buffer(verts.into_raw(),
                                                              &decl.decl,
                                                              flags.bits());
-            VertexBuffer { handle: handle, _phantom: PhantomData }
+            VertexBuffer { handle: handle, bgfx: bgfx, num: num, _vertex: PhantomData }
         }
     }
 
 }
 
-impl<'m> Drop for VertexBuffer<'m> {
+impl<'b, V: Vertex + Pod> VertexBuffer<'b, V> {
 
+    /// Creates a new vertex buffer from a copy of the given vertices, using the declaration of
+    /// their vertex type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the declaration of `V` does not match its size.
     #[inline]
-    fn drop(&mut self) {
-        unsafe { bgfx_sys::bgfx_destroy_vertex_buffer(self.handle) }
+    pub fn from_slice(bgfx: &'b Bgfx, vertices: &[V], flags: BufferFlags) -> VertexBuffer<'b, V> {
+        let decl = V::decl();
+        assert_vertex_stride::<V>(decl.stride());
+        let verts = Memory::copy(bgfx, vertices);
+        let num = vertices.len() as u32;
+        unsafe {
+            let handle = bgfx_sys::bgfx_create_vertex_buffer(verts.into_raw(),
+                                                             &decl.decl,
+                                                             flags.bits());
+            VertexBuffer { handle: handle, bgfx: bgfx, num: num, _vertex: PhantomData }
+        }
     }
 
 }
 
-/// Describes the structure of a vertex.
-pub struct VertexDecl {
-    decl: bgfx_sys::bgfx_vertex_decl_t,
-}
-
-impl VertexDecl {
+impl<'b, V> VertexBuffer<'b, V> {
 
-    /// Creates a new vertex declaration using a [`VertexDeclBuilder`].
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// bgfx::VertexDecl::new(None)
-    ///                  .add(bgfx::Attrib::Position, 3, bgfx::AttribType::Float)
-    ///                  .add(bgfx::Attrib::Color0, 4, bgfx::AttribType::Uint8(true))
-    ///                  .end();
-    /// ```
-    ///
-    /// [`VertexDeclBuilder`]: struct.VertexDeclBuilder.html
+    /// Gets the number of vertices in the buffer.
     #[inline]
-    pub fn new(renderer: Option<RendererType>) -> VertexDeclBuilder {
-        unsafe {
-            let renderer = mem::transmute(renderer.unwrap_or(RendererType::Noop));
-            let mut descr = VertexDeclBuilder { decl: mem::uninitialized() };
-            bgfx_sys::bgfx_vertex_decl_begin(&mut descr.decl, renderer);
-            descr
-        }
+    pub fn num_vertices(&self) -> u32 {
+        self.num
     }
 
 }
 
-/// Builder for `VertexDecl` instances.
-pub struct VertexDeclBuilder {
+impl<'b, V> Drop for VertexBuffer<'b, V> {
+
+    #[inline]
+    fn drop(&mut self) {
+        self.bgfx.destroy(Destroy::VertexBuffer(self.handle));
+    }
+
+}
+
+/// Texture.
+pub struct Texture<'b> {
+    handle: bgfx_sys::bgfx_texture_handle_t,
+    bgfx: &'b Bgfx,
+}
+
+impl<'b> Texture<'b> {
+
+    /// Creates a new 2D texture from bgfx-managed memory, holding the pixels of every layer and,
+    /// if `has_mips` is set, every mip level.
+    ///
+    /// Fails with `BgfxError::TextureSizeMismatch` if the size of the data does not match the
+    /// storage size bgfx calculates for the texture.
+    #[inline]
+    pub fn new_2d(data: Memory<'b>,
+                  width: u16,
+                  height: u16,
+                  has_mips: bool,
+                  num_layers: u16,
+                  format: TextureFormat)
+                  -> Result<Texture<'b>, BgfxError> {
+        let bgfx = data.bgfx;
+        unsafe {
+            let mut info: bgfx_sys::bgfx_texture_info_t = mem::zeroed();
+            bgfx_sys::bgfx_calc_texture_size(&mut info,
+                                             width,
+                                             height,
+                                             1,
+                                             false,
+                                             has_mips,
+                                             num_layers,
+                                             mem::transmute(format));
+            if info.storageSize != data.size {
+                return Err(BgfxError::TextureSizeMismatch);
+            }
+
+            let handle = bgfx_sys::bgfx_create_texture_2d(width,
+                                                          height,
+                                                          has_mips,
+                                                          num_layers,
+                                                          mem::transmute(format),
+                                                          0,
+                                                          data.into_raw());
+            Ok(Texture { handle: handle, bgfx: bgfx })
+        }
+    }
+
+}
+
+impl<'b> Drop for Texture<'b> {
+
+    #[inline]
+    fn drop(&mut self) {
+        self.bgfx.destroy(Destroy::Texture(self.handle));
+    }
+
+}
+
+/// Shader uniform.
+///
+/// Uniforms are matched to shader inputs by name, and their values are set per draw through
+/// [`Draw::uniform`], or [`Draw::texture`] for samplers.
+///
+/// [`Draw::uniform`]: struct.Draw.html#method.uniform
+/// [`Draw::texture`]: struct.Draw.html#method.texture
+pub struct Uniform<'b> {
+    handle: bgfx_sys::bgfx_uniform_handle_t,
+    bgfx: &'b Bgfx,
+    kind: UniformType,
+    num: u16,
+}
+
+impl<'b> Uniform<'b> {
+
+    /// Creates a new uniform. `num` is the number of elements, for array uniforms.
+    #[inline]
+    pub fn new(bgfx: &'b Bgfx, name: &str, kind: UniformType, num: u16) -> Uniform<'b> {
+        let name = ffi::CString::new(name).unwrap();
+        unsafe {
+            let handle = bgfx_sys::bgfx_create_uniform(name.as_ptr(), mem::transmute(kind), num);
+            Uniform { handle: handle, bgfx: bgfx, kind: kind, num: num }
+        }
+    }
+
+    /// Gets the type of the uniform.
+    #[inline]
+    pub fn kind(&self) -> UniformType {
+        self.kind
+    }
+
+    /// Gets the number of elements of the uniform.
+    #[inline]
+    pub fn num(&self) -> u16 {
+        self.num
+    }
+
+}
+
+impl<'b> Drop for Uniform<'b> {
+
+    #[inline]
+    fn drop(&mut self) {
+        self.bgfx.destroy(Destroy::Uniform(self.handle));
+    }
+
+}
+
+/// Resource handle waiting to be destroyed.
+enum Destroy {
+    IndexBuffer(bgfx_sys::bgfx_index_buffer_handle_t),
+    Program(bgfx_sys::bgfx_program_handle_t),
+    Shader(bgfx_sys::bgfx_shader_handle_t),
+    Texture(bgfx_sys::bgfx_texture_handle_t),
+    Uniform(bgfx_sys::bgfx_uniform_handle_t),
+    VertexBuffer(bgfx_sys::bgfx_vertex_buffer_handle_t),
+}
+
+impl Destroy {
+
+    fn destroy(self) {
+        unsafe {
+            match self {
+                Destroy::IndexBuffer(handle) => bgfx_sys::bgfx_destroy_index_buffer(handle),
+                Destroy::Program(handle) => bgfx_sys::bgfx_destroy_program(handle),
+                Destroy::Shader(handle) => bgfx_sys::bgfx_destroy_shader(handle),
+                Destroy::Texture(handle) => bgfx_sys::bgfx_destroy_texture(handle),
+                Destroy::Uniform(handle) => bgfx_sys::bgfx_destroy_uniform(handle),
+                Destroy::VertexBuffer(handle) => bgfx_sys::bgfx_destroy_vertex_buffer(handle),
+            }
+        }
+    }
+
+}
+
+/// Reference-counted resource, for assets used by many owners.
+///
+/// Cloning a shared resource only adds a reference to it. The resource is destroyed once the last
+/// reference has been dropped. Like any destruction, bgfx defers releasing the underlying handle
+/// until after the next call to [`Bgfx::frame`], so draws that still use it are unaffected.
+///
+/// Since resources can only be used on the API thread, the count is not atomic.
+///
+/// ```no_run
+/// # use bgfx::*;
+/// # static INDICES: [u16; 3] = [0, 1, 2];
+/// # let bgfx = Init::headless().init().unwrap();
+/// let ib = Shared::new(IndexBuffer::new(Memory::reference(&bgfx, &INDICES), BUFFER_NONE));
+/// let other = ib.clone();
+/// bgfx.set_index_buffer(&other);
+/// ```
+///
+/// [`Bgfx::frame`]: struct.Bgfx.html#method.frame
+pub struct Shared<R> {
+    resource: Rc<R>,
+}
+
+impl<R> Shared<R> {
+
+    /// Wraps a resource for sharing.
+    #[inline]
+    pub fn new(resource: R) -> Shared<R> {
+        Shared { resource: Rc::new(resource) }
+    }
+
+    /// Returns `true` if both references point to the same resource.
+    #[inline]
+    pub fn ptr_eq(this: &Shared<R>, other: &Shared<R>) -> bool {
+        Rc::ptr_eq(&this.resource, &other.resource)
+    }
+
+    /// Gets the number of references to the resource.
+    #[inline]
+    pub fn strong_count(this: &Shared<R>) -> usize {
+        Rc::strong_count(&this.resource)
+    }
+
+}
+
+impl<R> Clone for Shared<R> {
+
+    #[inline]
+    fn clone(&self) -> Shared<R> {
+        Shared { resource: self.resource.clone() }
+    }
+
+}
+
+impl<R> Deref for Shared<R> {
+
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &R {
+        &self.resource
+    }
+
+}
+
+impl<R> From<R> for Shared<R> {
+
+    #[inline]
+    fn from(resource: R) -> Shared<R> {
+        Shared::new(resource)
+    }
+
+}
+
+/// Vertex type with a known layout.
+///
+/// With the `derive` feature enabled, this can be derived for `#[repr(C)]` and
+/// `#[repr(C, packed)]` structs. Every field is given a vertex attribute through
+/// `#[bgfx(attrib = "...")]`, or is skipped as padding through `#[bgfx(skip)]`. The derive also
+/// implements [`Pod`], for which the struct has to be `Copy`.
+///
+/// The attribute type and component count are inferred for fields of type `f32`, `u8` and `i16`,
+/// and arrays of those. A `u32` field is taken to be four packed `u8` components, such as an ABGR
+/// color. They can also be set explicitly through `ty` and `count`. Integer attributes are
+/// normalized if `normalized` is given, and passed to the shader as integers if `as_int` is.
+///
+/// ```
+/// # #[macro_use] extern crate bgfx_derive;
+/// # extern crate bgfx;
+/// use bgfx::Vertex;
+///
+/// #[derive(Vertex, Copy, Clone)]
+/// #[repr(C)]
+/// struct PosColorTexVertex {
+///     #[bgfx(attrib = "Position")]
+///     pos: [f32; 3],
+///     #[bgfx(attrib = "Color0", normalized)]
+///     abgr: u32,
+///     #[bgfx(attrib = "TexCoord0", ty = "Int16", normalized)]
+///     uv: [i16; 2],
+/// }
+///
+/// # fn main() {
+/// let decl = PosColorTexVertex::decl();
+/// # }
+/// ```
+///
+/// The derive checks at compile time that each attribute occupies as many bytes as its field,
+/// and that the declaration has the same stride as the struct. bgfx pads some attributes, such
+/// as three `u8` components, which is caught here:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate bgfx_derive;
+/// # extern crate bgfx;
+/// #[derive(Vertex, Copy, Clone)]
+/// #[repr(C)]
+/// struct PosColorVertex {
+///     #[bgfx(attrib = "Position")]
+///     pos: [f32; 3],
+///     #[bgfx(attrib = "Color0", normalized)]
+///     rgb: [u8; 3],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// A bare `#[repr(packed)]` is rejected, since it leaves the order of the fields up to the
+/// compiler:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate bgfx_derive;
+/// # extern crate bgfx;
+/// #[derive(Vertex, Copy, Clone)]
+/// #[repr(packed)]
+/// struct PosVertex {
+///     #[bgfx(attrib = "Position")]
+///     pos: [f32; 3],
+/// }
+/// # fn main() {}
+/// ```
+///
+/// [`Pod`]: trait.Pod.html
+pub trait Vertex {
+    /// Gets the declaration describing the layout of this vertex type.
+    fn decl() -> VertexDecl;
+}
+
+/// Checks that a vertex declaration covers exactly one `V`, before bgfx reads a slice of them
+/// with its stride. `Vertex` can be implemented by hand, so this can not be taken for granted.
+fn assert_vertex_stride<V: Vertex>(stride: u16) {
+    assert!(stride as usize == mem::size_of::<V>(),
+            "vertex declaration does not match the size of the vertex type");
+}
+
+/// Describes the structure of a vertex.
+pub struct VertexDecl {
+    decl: bgfx_sys::bgfx_vertex_decl_t,
+}
+
+impl VertexDecl {
+
+    /// Gets the layout of the given attribute, or `None` if the declaration does not contain it.
+    ///
+    /// The layout is returned as the number of components, the component type, and the flags it
+    /// was added with.
+    pub fn attribute(&self, attrib: Attrib) -> Option<(u8, AttribType, AttribFlags)> {
+        let encoded = self.decl.attributes[attrib as usize];
+        if encoded == std::u16::MAX {
+            return None;
+        }
+
+        let count = (encoded & 3) as u8 + 1;
+        let mut flags = ATTRIB_NONE;
+        if encoded & (1 << 7) != 0 {
+            flags.insert(ATTRIB_NORMALIZED);
+        }
+        if encoded & (1 << 8) != 0 {
+            flags.insert(ATTRIB_AS_INT);
+        }
+        let kind = match (encoded >> 3) & 7 {
+            0 => AttribType::Uint8,
+            1 => AttribType::Uint10,
+            2 => AttribType::Int16,
+            3 => AttribType::Half,
+            _ => AttribType::Float,
+        };
+
+        Some((count, kind, flags))
+    }
+
+    /// Converts `count` vertices in `src`, laid out according to `src_decl`, into `dst`, laid out
+    /// according to `dst_decl`. Attributes missing from `src_decl` are zeroed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either buffer is too small to hold `count` vertices.
+    pub fn convert(dst_decl: &VertexDecl,
+                   dst: &mut [u8],
+                   src_decl: &VertexDecl,
+                   src: &[u8],
+                   count: u32) {
+        assert!(dst.len() >= count as usize * dst_decl.stride() as usize,
+                "destination buffer too small");
+        assert!(src.len() >= count as usize * src_decl.stride() as usize,
+                "source buffer too small");
+
+        unsafe {
+            bgfx_sys::bgfx_vertex_convert(&dst_decl.decl,
+                                          dst.as_mut_ptr() as *mut std::os::raw::c_void,
+                                          &src_decl.decl,
+                                          src.as_ptr() as *const std::os::raw::c_void,
+                                          count);
+        }
+    }
+
+    /// Returns whether the declaration contains the given attribute.
+    #[inline]
+    pub fn has(&self, attrib: Attrib) -> bool {
+        self.decl.attributes[attrib as usize] != std::u16::MAX
+    }
+
+    /// Creates a new vertex declaration using a [`VertexDeclBuilder`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bgfx::*;
+    ///
+    /// VertexDecl::new(None)
+    ///     .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+    ///     .add(Attrib::Color0, 4, AttribType::Uint8, ATTRIB_NORMALIZED)
+    ///     .end();
+    /// ```
+    ///
+    /// [`VertexDeclBuilder`]: struct.VertexDeclBuilder.html
+    #[inline]
+    pub fn new(renderer: Option<RendererType>) -> VertexDeclBuilder {
+        unsafe {
+            let renderer = mem::transmute(renderer.unwrap_or(RendererType::Noop));
+            let mut descr = VertexDeclBuilder { decl: mem::zeroed() };
+            bgfx_sys::bgfx_vertex_decl_begin(&mut descr.decl, renderer);
+            descr
+        }
+    }
+
+    /// Gets the byte offset of the given attribute within a vertex, or `None` if the declaration
+    /// does not contain it.
+    #[inline]
+    pub fn offset(&self, attrib: Attrib) -> Option<u16> {
+        if self.has(attrib) {
+            Some(self.decl.offset[attrib as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Encodes `input` into the given attribute of vertex `index` in `data`, converting it to the
+    /// attribute's type. Components beyond the attribute's count are ignored, and nothing is
+    /// written if the declaration does not contain the attribute.
+    ///
+    /// If `input_normalized` is set, the input for integer attributes is expected to be in the 0
+    /// to 1 range, or -1 to 1 for attributes passed as integers, and is scaled to the range of the
+    /// integer type. Otherwise it is converted as is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is too small to hold vertex `index`.
+    pub fn pack(&self,
+                attrib: Attrib,
+                input: [f32; 4],
+                input_normalized: bool,
+                data: &mut [u8],
+                index: u32) {
+        self.check_bounds(data.len(), index);
+        unsafe {
+            bgfx_sys::bgfx_vertex_pack(input.as_ptr(),
+                                       input_normalized,
+                                       mem::transmute(attrib),
+                                       &self.decl,
+                                       data.as_mut_ptr() as *mut std::os::raw::c_void,
+                                       index);
+        }
+    }
+
+    /// Gets the size of a vertex, in bytes.
+    #[inline]
+    pub fn stride(&self) -> u16 {
+        self.decl.stride
+    }
+
+    /// Decodes the given attribute of vertex `index` in `data`. Components beyond the attribute's
+    /// count are zero, as are all components if the declaration does not contain the attribute.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is too small to hold vertex `index`.
+    pub fn unpack(&self, attrib: Attrib, data: &[u8], index: u32) -> [f32; 4] {
+        self.check_bounds(data.len(), index);
+        let mut output = [0.0; 4];
+        unsafe {
+            bgfx_sys::bgfx_vertex_unpack(output.as_mut_ptr(),
+                                         mem::transmute(attrib),
+                                         &self.decl,
+                                         data.as_ptr() as *const std::os::raw::c_void,
+                                         index);
+        }
+        output
+    }
+
+    #[inline]
+    fn check_bounds(&self, len: usize, index: u32) {
+        assert!(len >= (index as usize + 1) * self.decl.stride as usize,
+                "vertex index out of bounds");
+    }
+
+}
+
+impl fmt::Debug for VertexDecl {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VertexDecl")
+            .field("stride", &self.decl.stride)
+            .field("attributes", &VertexDeclAttributes(self))
+            .finish()
+    }
+
+}
+
+/// Formats the attributes of a `VertexDecl`, mapped to their offsets and layouts.
+struct VertexDeclAttributes<'a>(&'a VertexDecl);
+
+impl<'a> fmt::Debug for VertexDeclAttributes<'a> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut attributes = f.debug_map();
+        for index in 0..bgfx_sys::bgfx_attrib_BGFX_ATTRIB_COUNT {
+            let attrib: Attrib = unsafe { mem::transmute(index) };
+            if let Some(attribute) = self.0.attribute(attrib) {
+                attributes.entry(&attrib, &(self.0.decl.offset[index as usize], attribute));
+            }
+        }
+        attributes.finish()
+    }
+
+}
+
+impl PartialEq for VertexDecl {
+
+    fn eq(&self, other: &VertexDecl) -> bool {
+        self.decl.stride == other.decl.stride && self.decl.offset == other.decl.offset &&
+        self.decl.attributes == other.decl.attributes
+    }
+
+}
+
+impl Eq for VertexDecl {}
+
+impl Hash for VertexDecl {
+
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.decl.stride.hash(state);
+        self.decl.offset.hash(state);
+        self.decl.attributes.hash(state);
+    }
+
+}
+
+/// Builder for `VertexDecl` instances.
+pub struct VertexDeclBuilder {
     decl: bgfx_sys::bgfx_vertex_decl_t,
 }
 
@@ -453,50 +1578,41 @@ impl VertexDeclBuilder {
 
     /// Adds a field to the structure descriptor. See [`VertexDecl::new`] for an example.
     ///
+    /// The field has `count` components of type `kind`. With `ATTRIB_NORMALIZED`, integer values
+    /// are mapped to the 0 to 1 range, or -1 to 1 for signed types. With `ATTRIB_AS_INT`, integer
+    /// values are passed to the shader as integers, rather than converted to floats.
+    ///
+    /// Only the integer types, `Uint8`, `Uint10` and `Int16`, can be normalized or passed as
+    /// integers. `Uint10` should have 3 or 4 components.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is not between 1 and 4, or if a non-integer type is normalized or passed
+    /// as integers.
+    ///
     /// [`VertexDecl::new`]: struct.VertexDecl.html#method.new
-    pub fn add(&mut self, attrib: Attrib, count: u8, kind: AttribType) -> &mut Self {
-        let mut normalized = false;
-        let mut as_int = false;
-
-        let kind = match kind {
-            AttribType::Uint8(n) => {
-                normalized = n;
-                bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_UINT8
-            }
-            AttribType::Int8(n) => {
-                normalized = n;
-                as_int = true;
-                bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_UINT8
-            }
-            AttribType::Uint10(n) => {
-                normalized = n;
-                bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_UINT10
-            }
-            AttribType::Int10(n) => {
-                normalized = n;
-                as_int = true;
-                bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_UINT10
-            }
-            AttribType::Uint16(n) => {
-                normalized = n;
-                bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_INT16
-            }
-            AttribType::Int16(n) => {
-                normalized = n;
-                as_int = true;
-                bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_INT16
-            }
-            AttribType::Half => bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_HALF,
-            AttribType::Float => bgfx_sys::bgfx_attrib_type_t::BGFX_ATTRIB_TYPE_FLOAT,
+    pub fn add(&mut self,
+               attrib: Attrib,
+               count: u8,
+               kind: AttribType,
+               flags: AttribFlags)
+               -> &mut Self {
+        let integer = match kind {
+            AttribType::Uint8 | AttribType::Uint10 | AttribType::Int16 => true,
+            AttribType::Half | AttribType::Float => false,
         };
 
+        assert!((1..=4).contains(&count), "attributes have between 1 and 4 components");
+        assert!(integer || flags.is_empty(),
+                "only integer attributes can be normalized or passed as integers");
+
         unsafe {
             bgfx_sys::bgfx_vertex_decl_add(&mut self.decl,
                                            mem::transmute(attrib),
                                            count,
-                                           kind,
-                                           normalized,
-                                           as_int);
+                                           mem::transmute(kind),
+                                           flags.contains(ATTRIB_NORMALIZED),
+                                           flags.contains(ATTRIB_AS_INT));
         }
 
         self
@@ -529,19 +1645,47 @@ impl VertexDeclBuilder {
 /// Acts as the library wrapper for bgfx. Any calls intended to be run on the main thread are
 /// exposed as functions on this object.
 ///
-/// It is created through a call to [`bgfx::init`], and will shut down bgfx when dropped.
+/// It is created through [`bgfx::Init`], and will shut down bgfx when dropped.
+///
+/// bgfx requires all API calls to be made from the thread that initialized it, so this object can
+/// not be sent to, or shared with, other threads.
+///
+/// ```compile_fail
+/// fn assert_sync<T: Sync>() {}
+/// assert_sync::<bgfx::Bgfx>();
+/// ```
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<bgfx::Bgfx>();
+/// ```
 ///
-/// [`bgfx::init`]: fn.init.html
+/// [`bgfx::Init`]: struct.Init.html
 pub struct Bgfx {
-    // This dummy field only exists so this type can't be publicly instantiated.
-    _dummy: u32,
+    pending: RefCell<Vec<Destroy>>,
+
+    // Keeps this type from being publicly instantiated, and from being `Send` or `Sync`.
+    _marker: PhantomData<*mut ()>,
 }
 
 impl Bgfx {
 
     #[inline]
     fn new() -> Bgfx {
-        Bgfx { _dummy: 0 }
+        Bgfx { pending: RefCell::new(Vec::new()), _marker: PhantomData }
+    }
+
+    /// Queues a dropped resource for destruction after the next frame.
+    #[inline]
+    fn destroy(&self, resource: Destroy) {
+        self.pending.borrow_mut().push(resource);
+    }
+
+    /// Destroys all resources dropped since the last frame.
+    fn destroy_pending(&self) {
+        for resource in self.pending.borrow_mut().drain(..) {
+            resource.destroy();
+        }
     }
 
     /// Clears the debug text overlay.
@@ -577,10 +1721,36 @@ impl Bgfx {
         unsafe { bgfx_sys::bgfx_dbg_text_printf(x, y, attr, text.as_ptr()) }
     }
 
+    /// Starts a draw call on the given view, using the given program. See [`Draw`].
+    ///
+    /// [`Draw`]: struct.Draw.html
+    #[inline]
+    pub fn draw<'a, 'b>(&'b self, view: u8, program: &'a Program<'b>) -> Draw<'a, 'b> {
+        Draw {
+            view: view,
+            program: program,
+            vertices: None,
+            indices: None,
+            transform: None,
+            state: RenderState::default(),
+            uniforms: Vec::new(),
+            textures: Vec::new(),
+        }
+    }
+
     /// Finish the frame, syncing up with the render thread. Returns an incrementing frame counter.
+    ///
+    /// Resources dropped before this call are destroyed once the frame has been submitted, so
+    /// draws submitted during the frame can still use them.
+    ///
+    /// If bgfx was initialized in [single-threaded] mode, the frame is rendered before returning.
+    ///
+    /// [single-threaded]: struct.Init.html#method.single_threaded
     #[inline]
     pub fn frame(&self, capture: bool) -> u32 {
-        unsafe { bgfx_sys::bgfx_frame(capture) }
+        let frame = unsafe { bgfx_sys::bgfx_frame(capture) };
+        self.destroy_pending();
+        frame
     }
 
     /// Gets the type of the renderer in use.
@@ -589,9 +1759,18 @@ impl Bgfx {
         unsafe { mem::transmute(bgfx_sys::bgfx_get_renderer_type()) }
     }
 
+    /// Gets the number of dropped resources waiting to be destroyed after the next frame.
+    #[inline]
+    pub fn pending_destructions(&self) -> usize {
+        self.pending.borrow().len()
+    }
+
     /// Resets the graphics device to the given size, with the given flags.
     #[inline]
     pub fn reset(&self, width: u16, height: u16, reset: ResetFlags) {
+        #[cfg(all(feature = "wayland", target_os = "linux"))]
+        wayland::resize(width, height);
+
         unsafe { bgfx_sys::bgfx_reset(width as u32, height as u32, reset.bits()) }
     }
 
@@ -603,12 +1782,29 @@ impl Bgfx {
 
     /// Sets the index buffer to use for rendering.
     #[inline]
-    pub fn set_index_buffer(&self, ibh: &IndexBuffer) {
-        // TODO: How to solve lifetimes...
+    pub fn set_index_buffer<'b>(&'b self, ibh: &IndexBuffer<'b>) {
         unsafe { bgfx_sys::bgfx_set_index_buffer(ibh.handle, 0, std::u32::MAX) }
     }
 
-    /// Sets the render state.
+    /// Sets a range of indices from the index buffer to use for rendering.
+    ///
+    /// In debug builds, panics if the range reaches past the end of the buffer. Release builds do
+    /// not check the range: an inverted range sets no indices, and a range reaching past the end
+    /// of the buffer is passed on to bgfx as is.
+    #[inline]
+    pub fn set_index_buffer_range<'b>(&'b self, ibh: &IndexBuffer<'b>, range: Range<u32>) {
+        set_index_range(ibh.handle, ibh.num, &range)
+    }
+
+    /// Sets the render state, along with the blending of the other render targets if it uses
+    /// independent blending.
+    #[inline]
+    pub fn set_render_state(&self, state: &RenderState) {
+        self.set_state(state.to_flags(), state.rgba())
+    }
+
+    /// Sets the render state. `rgba` is either the blend factor color, or the blending of the
+    /// second to fourth render targets if `STATE_BLEND_INDEPENDENT` is set.
     #[inline]
     pub fn set_state(&self, state: StateFlags, rgba: Option<u32>) {
         unsafe { bgfx_sys::bgfx_set_state(state.bits(), rgba.unwrap_or(0)) }
@@ -625,11 +1821,24 @@ impl Bgfx {
 
     /// Sets the vertex buffer to use for rendering.
     #[inline]
-    pub fn set_vertex_buffer(&self, stream: u8, vbh: &VertexBuffer) {
-        // TODO: How to solve lifetimes...
+    pub fn set_vertex_buffer<'b, V>(&'b self, stream: u8, vbh: &VertexBuffer<'b, V>) {
         unsafe { bgfx_sys::bgfx_set_vertex_buffer(stream, vbh.handle, 0, std::u32::MAX) }
     }
 
+    /// Sets a range of vertices from the vertex buffer to use for rendering. Indices are relative
+    /// to the start of the range.
+    ///
+    /// In debug builds, panics if the range reaches past the end of the buffer. Release builds do
+    /// not check the range: an inverted range sets no vertices, and a range reaching past the end
+    /// of the buffer is passed on to bgfx as is.
+    #[inline]
+    pub fn set_vertex_buffer_range<'b, V>(&'b self,
+                                          stream: u8,
+                                          vbh: &VertexBuffer<'b, V>,
+                                          range: Range<u32>) {
+        set_vertex_range(stream, vbh.handle, vbh.num, &range)
+    }
+
     /// Sets the options to use when clearing the given view.
     #[inline]
     pub fn set_view_clear(&self, id: u8, flags: ClearFlags, rgba: u32, depth: f32, stencil: u8) {
@@ -654,7 +1863,7 @@ impl Bgfx {
 
     /// Submit a primitive for rendering. Returns the number of draw calls used.
     #[inline]
-    pub fn submit(&self, view: u8, program: &Program, preserve_state: bool) -> u32 {
+    pub fn submit<'b>(&'b self, view: u8, program: &Program<'b>, preserve_state: bool) -> u32 {
         unsafe { bgfx_sys::bgfx_submit(view, program.handle, 0, preserve_state) }
     }
 
@@ -672,23 +1881,286 @@ impl Drop for Bgfx {
 
     #[inline]
     fn drop(&mut self) {
+        self.destroy_pending();
         unsafe { bgfx_sys::bgfx_shutdown() }
+        INITIALIZED.store(false, Ordering::SeqCst);
     }
 
 }
 
-/// Pump the render thread.
+/// Draw call.
+///
+/// Collects everything a draw uses, and validates it before issuing the bgfx calls in one go on
+/// [`submit`]. Unlike the state set directly on [`Bgfx`], nothing carries over from previous
+/// draws: the render state defaults to [`RenderState::default`], and the transform to identity.
+/// Any state set on [`Bgfx`] since the last submit is discarded.
+///
+/// ```no_run
+/// # use bgfx::*;
+/// # fn draw(bgfx: &Bgfx, program: &Program, vb: &VertexBuffer, ib: &IndexBuffer,
+/// #         mtx: &[f32; 16]) {
+/// bgfx.draw(0, program)
+///     .vertices(vb)
+///     .index_range(ib, 6..12)
+///     .transform(mtx)
+///     .state(RenderState::alpha_blend())
+///     .submit()
+///     .unwrap();
+/// # }
+/// ```
 ///
-/// This should be called repeatedly on the render thread.
+/// [`submit`]: #method.submit
+/// [`Bgfx`]: struct.Bgfx.html
+/// [`RenderState::default`]: struct.RenderState.html
+pub struct Draw<'a, 'b: 'a> {
+    view: u8,
+    program: &'a Program<'b>,
+    vertices: Option<(bgfx_sys::bgfx_vertex_buffer_handle_t, u32, Range<u32>)>,
+    indices: Option<(&'a IndexBuffer<'b>, Range<u32>)>,
+    transform: Option<[f32; 16]>,
+    state: RenderState,
+    uniforms: Vec<(&'a Uniform<'b>, &'a [f32])>,
+    textures: Vec<(u8, &'a Uniform<'b>, &'a Texture<'b>)>,
+}
+
+impl<'a, 'b> Draw<'a, 'b> {
+
+    /// Draws the given range of indices from the index buffer.
+    #[inline]
+    pub fn index_range(&mut self, ib: &'a IndexBuffer<'b>, range: Range<u32>) -> &mut Self {
+        self.indices = Some((ib, range));
+        self
+    }
+
+    /// Draws all indices of the index buffer.
+    #[inline]
+    pub fn indices(&mut self, ib: &'a IndexBuffer<'b>) -> &mut Self {
+        self.index_range(ib, 0..ib.num)
+    }
+
+    /// Sets the render state.
+    #[inline]
+    pub fn state(&mut self, state: RenderState) -> &mut Self {
+        self.state = state;
+        self
+    }
+
+    /// Validates the draw, and submits it for rendering. Returns the number of draw calls used.
+    ///
+    /// Fails without issuing any bgfx calls if no vertex buffer has been set, if a vertex or index
+    /// range reaches past the end of its buffer, or if a uniform value does not match its uniform.
+    pub fn submit(&self) -> Result<u32, BgfxError> {
+        let (vb, num_vertices, vertices) = match self.vertices {
+            Some((vb, num, ref vertices)) => (vb, num, vertices),
+            None => return Err(BgfxError::MissingVertices),
+        };
+
+        if !range_in_bounds(vertices, num_vertices) {
+            return Err(BgfxError::RangeOutOfBounds);
+        }
+
+        if let Some((ib, ref indices)) = self.indices {
+            if !range_in_bounds(indices, ib.num) {
+                return Err(BgfxError::RangeOutOfBounds);
+            }
+        }
+
+        for &(uniform, value) in &self.uniforms {
+            let components = uniform.kind.components();
+            let num = value.len() / components;
+            if uniform.kind == UniformType::Sampler || value.len() % components != 0 || num == 0 ||
+               num > uniform.num as usize {
+                return Err(BgfxError::UniformMismatch);
+            }
+        }
+
+        if self.textures.iter().any(|&(_, sampler, _)| sampler.kind != UniformType::Sampler) {
+            return Err(BgfxError::UniformMismatch);
+        }
+
+        unsafe {
+            // Drop any state set through `Bgfx` since the last submit, such as an index buffer,
+            // so it does not leak into this draw.
+            bgfx_sys::bgfx_discard();
+
+            if let Some(ref mtx) = self.transform {
+                bgfx_sys::bgfx_set_transform(mtx.as_ptr() as *const std::os::raw::c_void, 1);
+            }
+
+            set_vertex_range(0, vb, num_vertices, vertices);
+
+            if let Some((ib, ref indices)) = self.indices {
+                set_index_range(ib.handle, ib.num, indices);
+            }
+
+            bgfx_sys::bgfx_set_state(self.state.to_flags().bits(),
+                                     self.state.rgba().unwrap_or(0));
+
+            for &(uniform, value) in &self.uniforms {
+                let num = value.len() / uniform.kind.components();
+                bgfx_sys::bgfx_set_uniform(uniform.handle,
+                                           value.as_ptr() as *const std::os::raw::c_void,
+                                           num as u16);
+            }
+
+            for &(stage, sampler, texture) in &self.textures {
+                bgfx_sys::bgfx_set_texture(stage, sampler.handle, texture.handle, std::u32::MAX);
+            }
+
+            Ok(bgfx_sys::bgfx_submit(self.view, self.program.handle, 0, false))
+        }
+    }
+
+    /// Binds a texture to the given stage, through a `UniformType::Sampler` uniform.
+    #[inline]
+    pub fn texture(&mut self,
+                   stage: u8,
+                   sampler: &'a Uniform<'b>,
+                   texture: &'a Texture<'b>)
+                   -> &mut Self {
+        self.textures.push((stage, sampler, texture));
+        self
+    }
+
+    /// Sets the model transform.
+    #[inline]
+    pub fn transform(&mut self, mtx: &[f32; 16]) -> &mut Self {
+        self.transform = Some(*mtx);
+        self
+    }
+
+    /// Sets the value of a uniform. The value holds one or more elements of the uniform's type,
+    /// such as 4 floats per element of a `UniformType::Vec4` uniform.
+    #[inline]
+    pub fn uniform(&mut self, uniform: &'a Uniform<'b>, value: &'a [f32]) -> &mut Self {
+        self.uniforms.push((uniform, value));
+        self
+    }
+
+    /// Draws the given range of vertices from the vertex buffer.
+    #[inline]
+    pub fn vertex_range<V>(&mut self,
+                           vb: &'a VertexBuffer<'b, V>,
+                           range: Range<u32>)
+                           -> &mut Self {
+        self.vertices = Some((vb.handle, vb.num, range));
+        self
+    }
+
+    /// Draws all vertices of the vertex buffer.
+    #[inline]
+    pub fn vertices<V>(&mut self, vb: &'a VertexBuffer<'b, V>) -> &mut Self {
+        self.vertex_range(vb, 0..vb.num)
+    }
+
+}
+
+/// Whether `range` is a valid range of elements within a buffer holding `num` elements.
+#[inline]
+fn range_in_bounds(range: &Range<u32>, num: u32) -> bool {
+    range.start <= range.end && range.end <= num
+}
+
+/// Sets a range of indices from an index buffer holding `num` indices.
 #[inline]
-pub fn render_frame() -> RenderFrame {
-    unsafe { mem::transmute(bgfx_sys::bgfx_render_frame()) }
+fn set_index_range(handle: bgfx_sys::bgfx_index_buffer_handle_t, num: u32, range: &Range<u32>) {
+    debug_assert!(range_in_bounds(range, num),
+                  "index range {:?} out of bounds for {} indices",
+                  range,
+                  num);
+    let count = range.end.saturating_sub(range.start);
+    unsafe { bgfx_sys::bgfx_set_index_buffer(handle, range.start, count) }
+}
+
+/// Sets a range of vertices from a vertex buffer holding `num` vertices.
+#[inline]
+fn set_vertex_range(stream: u8,
+                    handle: bgfx_sys::bgfx_vertex_buffer_handle_t,
+                    num: u32,
+                    range: &Range<u32>) {
+    debug_assert!(range_in_bounds(range, num),
+                  "vertex range {:?} out of bounds for {} vertices",
+                  range,
+                  num);
+    let count = range.end.saturating_sub(range.start);
+    unsafe { bgfx_sys::bgfx_set_vertex_buffer(stream, handle, range.start, count) }
+}
+
+/// Whether bgfx is currently initialized. bgfx only supports a single instance per process.
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// The thread that claimed the render thread, if any.
+static RENDER_THREAD: Mutex<Option<ThreadId>> = Mutex::new(None);
+
+/// Claims the render thread for the calling thread. The thread that already owns it may claim it
+/// again.
+fn claim_render_thread() -> Result<(), BgfxError> {
+    let mut owner = RENDER_THREAD.lock().unwrap_or_else(|err| err.into_inner());
+    match *owner {
+        None => {
+            *owner = Some(thread::current().id());
+            Ok(())
+        }
+        Some(id) if id == thread::current().id() => Ok(()),
+        Some(_) => Err(BgfxError::RenderThreadClaimed),
+    }
+}
+
+/// Token for the thread acting as the bgfx render thread.
+///
+/// bgfx treats the first thread to pump it as the render thread for the rest of the process, so
+/// the token is bound to the thread that first claimed it. That thread can claim it again while
+/// bgfx is shut down, for example to initialize bgfx anew after a previous instance was dropped.
+///
+/// ```compile_fail
+/// fn assert_send<T: Send>() {}
+/// assert_send::<bgfx::RenderThread>();
+/// ```
+pub struct RenderThread {
+    _marker: PhantomData<*mut ()>,
+}
+
+impl RenderThread {
+
+    /// Claims the calling thread as the render thread, and pumps it once to tell bgfx about it.
+    ///
+    /// This must happen *before* bgfx is initialized on the main thread, or bgfx creates its own
+    /// render thread. Returns `BgfxError::AlreadyInitialized` if bgfx is currently initialized,
+    /// and `BgfxError::RenderThreadClaimed` if another thread has already claimed the render
+    /// thread, including through a [single-threaded] initialization.
+    ///
+    /// [single-threaded]: struct.Init.html#method.single_threaded
+    pub fn claim() -> Result<RenderThread, BgfxError> {
+        if INITIALIZED.load(Ordering::SeqCst) {
+            return Err(BgfxError::AlreadyInitialized);
+        }
+
+        claim_render_thread()?;
+
+        // Pumping the render thread before `bgfx_init` tells bgfx not to create a render thread.
+        // bgfx has no context yet, so this renders nothing.
+        unsafe { bgfx_sys::bgfx_render_frame() };
+        Ok(RenderThread { _marker: PhantomData })
+    }
+
+    /// Pump the render thread.
+    ///
+    /// This should be called repeatedly on the render thread.
+    #[inline]
+    pub fn render_frame(&self) -> RenderFrame {
+        unsafe { mem::transmute(bgfx_sys::bgfx_render_frame()) }
+    }
+
 }
 
 /// Platform data initializer.
 ///
 /// This should be applied *only once*, before bgfx is used.
 ///
+/// On Linux, either an X11 display and window, or a Wayland display and EGL window should be set.
+/// Wayland support requires building with the `wayland` feature, which in turn drops support for
+/// X11.
+///
 /// # Example
 ///
 /// ```should_panic
@@ -703,6 +2175,9 @@ pub fn render_frame() -> RenderFrame {
 /// ```
 pub struct PlatformData {
     data: bgfx_sys::bgfx_platform_data,
+    wayland: bool,
+    #[cfg(all(feature = "wayland", target_os = "linux"))]
+    egl_window: Option<wayland::EglWindow>,
 }
 
 impl PlatformData {
@@ -719,12 +2194,111 @@ impl PlatformData {
                 backBufferDS: ptr::null_mut(),
                 session: ptr::null_mut(),
             },
+            wayland: false,
+            #[cfg(all(feature = "wayland", target_os = "linux"))]
+            egl_window: None,
         }
     }
 
+    /// Creates a PlatformData instance from any window implementing the [`raw-window-handle`]
+    /// traits, such as those created by winit, sdl2 or glfw.
+    ///
+    /// Xlib, Xcb, Wayland, Win32, AppKit and UIKit handles are supported. The display and window
+    /// handles must belong to the same windowing system, or this fails with `InvalidWindow`.
+    /// Other windowing systems fail with `UnsupportedWindowHandle`.
+    ///
+    /// An Xcb connection is passed on as the display, so it is only usable with renderers that
+    /// accept one in place of an Xlib display.
+    ///
+    /// Wayland handles require the `wayland` feature, and fail with `WaylandUnsupported`
+    /// without it. bgfx renders to Wayland through EGL, so an EGL window is created for the
+    /// surface. The crate keeps it alive for the rest of the process once the platform data has
+    /// been applied, and resizes it along with [`Bgfx::reset`]. To manage the EGL window
+    /// yourself, set it through [`wayland_egl_window`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let window = winit::window::Window::new(&event_loop).unwrap();
+    /// bgfx::PlatformData::from_window_handle(&window)
+    ///     .expect("Unsupported window")
+    ///     .apply()
+    ///     .expect("Could not set platform data");
+    /// ```
+    ///
+    /// [`raw-window-handle`]: https://crates.io/crates/raw-window-handle
+    /// [`Bgfx::reset`]: struct.Bgfx.html#method.reset
+    /// [`wayland_egl_window`]: #method.wayland_egl_window
+    pub fn from_window_handle<W>(window: &W) -> Result<PlatformData, BgfxError>
+        where W: HasRawWindowHandle + HasRawDisplayHandle
+    {
+        let mut data = PlatformData::new();
+
+        match (window.raw_display_handle(), window.raw_window_handle()) {
+            (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window)) => {
+                data.display(display.display);
+                data.window(window.window as *mut std::os::raw::c_void);
+            }
+            (RawDisplayHandle::Xcb(display), RawWindowHandle::Xcb(window)) => {
+                data.display(display.connection);
+                data.window(window.window as usize as *mut std::os::raw::c_void);
+            }
+            (RawDisplayHandle::Wayland(display), RawWindowHandle::Wayland(window)) => {
+                data.wayland_display(display.display);
+                data.wayland_surface(window.surface)?;
+            }
+            (RawDisplayHandle::Windows(_), RawWindowHandle::Win32(window)) => {
+                data.window(window.hwnd);
+            }
+            (RawDisplayHandle::AppKit(_), RawWindowHandle::AppKit(window)) => {
+                // bgfx prefers the NSWindow, but will make do with the view if that's all we have.
+                if window.ns_window.is_null() {
+                    data.window(window.ns_view);
+                } else {
+                    data.window(window.ns_window);
+                }
+            }
+            (RawDisplayHandle::UiKit(_), RawWindowHandle::UiKit(window)) => {
+                data.window(window.ui_view);
+            }
+            (RawDisplayHandle::Xlib(_), _) |
+            (RawDisplayHandle::Xcb(_), _) |
+            (RawDisplayHandle::Wayland(_), _) |
+            (RawDisplayHandle::Windows(_), _) |
+            (RawDisplayHandle::AppKit(_), _) |
+            (RawDisplayHandle::UiKit(_), _) => return Err(BgfxError::InvalidWindow),
+            _ => return Err(BgfxError::UnsupportedWindowHandle),
+        }
+
+        Ok(data)
+    }
+
     /// Apply the platform configuration.
+    ///
+    /// If a Wayland display or EGL window has been set, this fails with `WaylandUnsupported`
+    /// unless the crate was built with the `wayland` feature. Conversely, Linux builds with the
+    /// `wayland` feature fail with `UnsupportedWindowHandle` for any other window.
+    ///
+    /// If a [`back_buffer`] has been set, bgfx renders into it rather than into a window, and
+    /// neither the display nor the window are required.
+    ///
+    /// [`back_buffer`]: #method.back_buffer
     pub fn apply(&mut self) -> Result<(), BgfxError> {
-        if self.data.ndt == ptr::null_mut() && cfg!(target_os = "linux") {
+        // When rendering into an externally owned backbuffer, bgfx never touches the window.
+        let external = self.data.backBuffer != ptr::null_mut();
+
+        if self.wayland && !cfg!(feature = "wayland") {
+            Err(BgfxError::WaylandUnsupported)
+        } else if cfg!(all(feature = "wayland", target_os = "linux")) && !self.wayland &&
+                  !external {
+            // bgfx is built for EGL on Wayland, and can not render to an X11 window.
+            Err(BgfxError::UnsupportedWindowHandle)
+        } else if external {
+            unsafe {
+                bgfx_sys::bgfx_set_platform_data(&mut self.data);
+            }
+            Ok(())
+        } else if self.data.ndt == ptr::null_mut() && (self.wayland || cfg!(target_os = "linux")) {
             Err(BgfxError::InvalidDisplay)
         } else if self.data.nwh == ptr::null_mut() {
             Err(BgfxError::InvalidWindow)
@@ -732,10 +2306,41 @@ impl PlatformData {
             unsafe {
                 bgfx_sys::bgfx_set_platform_data(&mut self.data);
             }
+
+            #[cfg(all(feature = "wayland", target_os = "linux"))]
+            {
+                if self.wayland {
+                    wayland::install(self.egl_window.take());
+                }
+            }
+
             Ok(())
         }
     }
 
+    /// Sets an externally owned backbuffer for bgfx to render into, instead of creating its own
+    /// swap chain for the window. This is the GL framebuffer object, or the D3D render target
+    /// view, and should be paired with the [`context`] it belongs to.
+    ///
+    /// This allows embedding bgfx into an engine or toolkit which owns the swap chain, such as
+    /// rendering into an editor viewport.
+    ///
+    /// [`context`]: #method.context
+    #[inline]
+    pub fn back_buffer(&mut self, back_buffer: *mut std::os::raw::c_void) -> &mut Self {
+        self.data.backBuffer = back_buffer;
+        self
+    }
+
+    /// Sets the depth/stencil buffer belonging to an externally owned [`back_buffer`].
+    ///
+    /// [`back_buffer`]: #method.back_buffer
+    #[inline]
+    pub fn back_buffer_ds(&mut self, back_buffer_ds: *mut std::os::raw::c_void) -> &mut Self {
+        self.data.backBufferDS = back_buffer_ds;
+        self
+    }
+
     /// Sets the GL context to use.
     #[inline]
     pub fn context(&mut self, context: *mut std::os::raw::c_void) -> &mut Self {
@@ -747,9 +2352,64 @@ impl PlatformData {
     #[inline]
     pub fn display(&mut self, display: *mut std::os::raw::c_void) -> &mut Self {
         self.data.ndt = display;
+        self.wayland = false;
+        self
+    }
+
+    /// Sets the HMD session to use, such as an `ovrSession` for the Oculus SDK.
+    #[inline]
+    pub fn session(&mut self, session: *mut std::os::raw::c_void) -> &mut Self {
+        self.data.session = session;
+        self
+    }
+
+    /// Sets the Wayland display (`wl_display`) to use. Requires the `wayland` feature.
+    #[inline]
+    pub fn wayland_display(&mut self, display: *mut std::os::raw::c_void) -> &mut Self {
+        self.data.ndt = display;
+        self.wayland = true;
+        self
+    }
+
+    /// Sets the Wayland EGL window (`wl_egl_window`) to render to. Requires the `wayland`
+    /// feature.
+    ///
+    /// bgfx renders through EGL, which takes an EGL window rather than the `wl_surface` itself.
+    /// Create it for the surface through `wl_egl_window_create`, resize it through
+    /// `wl_egl_window_resize` along with [`Bgfx::reset`], and only destroy it through
+    /// `wl_egl_window_destroy` once bgfx has been shut down.
+    ///
+    /// [`Bgfx::reset`]: struct.Bgfx.html#method.reset
+    #[inline]
+    pub fn wayland_egl_window(&mut self, window: *mut std::os::raw::c_void) -> &mut Self {
+        self.data.nwh = window;
+        self.wayland = true;
+        #[cfg(all(feature = "wayland", target_os = "linux"))]
+        {
+            self.egl_window = None;
+        }
         self
     }
 
+    /// Creates an EGL window for a Wayland surface, and sets it as the window to render to.
+    #[cfg(all(feature = "wayland", target_os = "linux"))]
+    fn wayland_surface(&mut self, surface: *mut std::os::raw::c_void) -> Result<(), BgfxError> {
+        if surface.is_null() {
+            return Err(BgfxError::InvalidWindow);
+        }
+
+        let egl_window = wayland::EglWindow::new(surface).ok_or(BgfxError::InvalidWindow)?;
+        self.wayland_egl_window(egl_window.as_ptr());
+        self.egl_window = Some(egl_window);
+        Ok(())
+    }
+
+    /// Wayland surfaces can not be rendered to without the `wayland` feature.
+    #[cfg(not(all(feature = "wayland", target_os = "linux")))]
+    fn wayland_surface(&mut self, _surface: *mut std::os::raw::c_void) -> Result<(), BgfxError> {
+        Err(BgfxError::WaylandUnsupported)
+    }
+
     /// Sets the handle to the window to use.
     #[inline]
     pub fn window(&mut self, window: *mut std::os::raw::c_void) -> &mut Self {
@@ -759,25 +2419,239 @@ impl PlatformData {
 
 }
 
-/// Initializes bgfx.
+/// bgfx initializer.
+///
+/// Collects the options to initialize bgfx with. Any option that is not set keeps the bgfx
+/// default. Once configured, [`init`] initializes bgfx and returns the [`Bgfx`] object used to
+/// access the API.
+///
+/// This must be used on the main thread after setting the platform data. See [`PlatformData`].
+///
+/// # Example
 ///
-/// This must be called on the main thread after setting the platform data. See [`PlatformData`].
+/// ```no_run
+/// let bgfx = bgfx::Init::new()
+///     .renderer(bgfx::RendererType::OpenGL)
+///     .vendor(bgfx::Vendor::Nvidia)
+///     .resolution(1280, 720)
+///     .reset(bgfx::RESET_VSYNC)
+///     .init()
+///     .expect("Failed to initialize bgfx");
+/// ```
 ///
+/// [`init`]: #method.init
+/// [`Bgfx`]: struct.Bgfx.html
 /// [`PlatformData`]: struct.PlatformData.html
-pub fn init(renderer: RendererType,
-            vendor_id: Option<u16>,
-            device_id: Option<u16>)
-            -> Result<Bgfx, BgfxError> {
-    let vendor = vendor_id.unwrap_or(PCI_ID_NONE);
-    let device = device_id.unwrap_or(0);
+pub struct Init {
+    renderer: RendererType,
+    vendor: Vendor,
+    device: u16,
+    callback: *mut bgfx_sys::bgfx_callback_interface_t,
+    allocator: *mut bgfx_sys::bgfx_allocator_interface_t,
+    width: u16,
+    height: u16,
+    reset: ResetFlags,
+    single_threaded: bool,
+}
 
-    unsafe {
-        let success = bgfx_sys::bgfx_init(mem::transmute(renderer),
-                                          vendor,
-                                          device,
-                                          ptr::null_mut(),
-                                          ptr::null_mut());
+impl Init {
+
+    /// Creates a new initializer using the default options.
+    #[inline]
+    pub fn new() -> Init {
+        Init {
+            renderer: RendererType::Default,
+            vendor: Vendor::None,
+            device: 0,
+            callback: ptr::null_mut(),
+            allocator: ptr::null_mut(),
+            width: 1280,
+            height: 720,
+            reset: RESET_NONE,
+            single_threaded: false,
+        }
+    }
+
+    /// Creates a new initializer for running bgfx without a window, using the `Noop` renderer.
+    ///
+    /// No platform data has to be applied before initializing, and there is no need to claim a
+    /// [`RenderThread`], as bgfx will create its own render thread. Resources can be created and
+    /// draw calls submitted as usual, but nothing is rendered.
+    ///
+    /// This does not initialize bgfx in [single-threaded] mode, as that binds the render thread
+    /// to the initializing thread for the rest of the process. Any thread can initialize a
+    /// headless instance, once the previous one has been dropped, such as each test in a test
+    /// binary. Single-threaded mode can still be enabled on the returned initializer.
+    ///
+    /// [`RenderThread`]: struct.RenderThread.html
+    /// [single-threaded]: #method.single_threaded
+    #[inline]
+    pub fn headless() -> Init {
+        let mut init = Init::new();
+        init.renderer(RendererType::Noop);
+        init
+    }
+
+    /// Sets the allocator bgfx should use for its internal allocations. If not set, bgfx uses its
+    /// own default allocator.
+    ///
+    /// # Safety
+    ///
+    /// The allocator must be valid until bgfx has been shut down, and must be safe to use from
+    /// any thread. bgfx allocates from both the API thread and the render thread, and `Init` is
+    /// `Send`, so bgfx may be initialized on another thread than the one setting the allocator.
+    #[inline]
+    pub unsafe fn allocator(&mut self,
+                            allocator: *mut bgfx_sys::bgfx_allocator_interface_t)
+                            -> &mut Self {
+        self.allocator = allocator;
+        self
+    }
 
-        if success { Ok(Bgfx::new()) } else { Err(BgfxError::InitFailed) }
+    /// Sets the callback interface bgfx should report to. If not set, bgfx uses its own default
+    /// callbacks.
+    ///
+    /// # Safety
+    ///
+    /// The callback interface must be valid until bgfx has been shut down, and must be safe to
+    /// call from any thread. bgfx calls it from both the API thread and the render thread, and
+    /// `Init` is `Send`, so bgfx may be initialized on another thread than the one setting the
+    /// callback.
+    #[inline]
+    pub unsafe fn callback(&mut self,
+                           callback: *mut bgfx_sys::bgfx_callback_interface_t)
+                           -> &mut Self {
+        self.callback = callback;
+        self
+    }
+
+    /// Sets the device ID of the adapter to use. Only used if a [`vendor`] has been set.
+    ///
+    /// [`vendor`]: #method.vendor
+    #[inline]
+    pub fn device(&mut self, device: u16) -> &mut Self {
+        self.device = device;
+        self
+    }
+
+    /// Sets the renderer backend to use. Defaults to `RendererType::Default`.
+    #[inline]
+    pub fn renderer(&mut self, renderer: RendererType) -> &mut Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Sets the flags to reset the graphics device with after initialization.
+    #[inline]
+    pub fn reset(&mut self, reset: ResetFlags) -> &mut Self {
+        self.reset = reset;
+        self
+    }
+
+    /// Sets the initial backbuffer resolution. Defaults to 1280x720.
+    #[inline]
+    pub fn resolution(&mut self, width: u16, height: u16) -> &mut Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets whether bgfx should run in single-threaded mode. Defaults to `false`.
+    ///
+    /// In single-threaded mode, the thread initializing bgfx also acts as the render thread. No
+    /// separate render thread is created, and [`Bgfx::frame`] renders the frame before returning.
+    /// This is useful for debugging, and for simple tools that have no need for a separate render
+    /// thread.
+    ///
+    /// Initializing fails with `BgfxError::RenderThreadClaimed` if another thread has claimed the
+    /// [`RenderThread`].
+    ///
+    /// [`RenderThread`]: struct.RenderThread.html
+    /// [`Bgfx::frame`]: struct.Bgfx.html#method.frame
+    #[inline]
+    pub fn single_threaded(&mut self, single_threaded: bool) -> &mut Self {
+        self.single_threaded = single_threaded;
+        self
+    }
+
+    /// Sets the vendor of the adapter to use. Defaults to `Vendor::None`, which lets bgfx pick.
+    #[inline]
+    pub fn vendor(&mut self, vendor: Vendor) -> &mut Self {
+        self.vendor = vendor;
+        self
+    }
+
+    /// Initializes bgfx using the configured options.
+    ///
+    /// Returns `BgfxError::AlreadyInitialized` if bgfx is already initialized, as only one
+    /// instance can exist at a time.
+    pub fn init(&mut self) -> Result<Bgfx, BgfxError> {
+        if self.renderer != RendererType::Default &&
+           !supported_renderers().contains(&self.renderer) {
+            return Err(BgfxError::UnsupportedRenderer(self.renderer));
+        }
+
+        if INITIALIZED.swap(true, Ordering::SeqCst) {
+            return Err(BgfxError::AlreadyInitialized);
+        }
+
+        if self.single_threaded {
+            if let Err(err) = claim_render_thread() {
+                INITIALIZED.store(false, Ordering::SeqCst);
+                return Err(err);
+            }
+        }
+
+        // bgfx creates its EGL surface on init, at the current size of the EGL window.
+        #[cfg(all(feature = "wayland", target_os = "linux"))]
+        wayland::resize(self.width, self.height);
+
+        unsafe {
+            // Pumping the render thread from the thread calling `bgfx_init`, before calling it,
+            // tells bgfx not to create a render thread, and to render as part of `bgfx_frame`.
+            if self.single_threaded {
+                bgfx_sys::bgfx_render_frame();
+            }
+
+            let success = bgfx_sys::bgfx_init(mem::transmute(self.renderer),
+                                              self.vendor as u16,
+                                              self.device,
+                                              self.callback,
+                                              self.allocator);
+
+            if !success {
+                INITIALIZED.store(false, Ordering::SeqCst);
+                return Err(BgfxError::InitFailed);
+            }
+
+            bgfx_sys::bgfx_reset(self.width as u32, self.height as u32, self.reset.bits());
+        }
+
+        Ok(Bgfx::new())
+    }
+
+}
+
+// The safety requirements of `callback` and `allocator` require both to be usable from any thread,
+// so the initializer may be created on one thread and used to initialize bgfx on another.
+unsafe impl Send for Init {}
+
+impl Default for Init {
+    #[inline]
+    fn default() -> Init {
+        Init::new()
+    }
+}
+
+/// Gets the renderers supported on this platform, in the order bgfx prefers them.
+///
+/// Can be called before bgfx has been initialized, for instance to let the user pick a backend.
+pub fn supported_renderers() -> Vec<RendererType> {
+    let count = bgfx_sys::bgfx_renderer_type_BGFX_RENDERER_TYPE_COUNT;
+    let mut supported = [0; bgfx_sys::bgfx_renderer_type_BGFX_RENDERER_TYPE_COUNT as usize];
+
+    unsafe {
+        let num = bgfx_sys::bgfx_get_supported_renderers(count as u8, supported.as_mut_ptr());
+        supported[..num as usize].iter().map(|&r| mem::transmute(r)).collect()
     }
 }
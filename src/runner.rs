@@ -0,0 +1,158 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+//! Windowing-agnostic render thread runner.
+
+use std::cell::Cell;
+use std::panic;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender, TryRecvError, channel};
+use std::thread;
+use std::time::Duration;
+
+use super::{Bgfx, BgfxError, Init, PlatformData, RenderFrame, RenderThread};
+
+/// Windowing system driven by [`run`].
+///
+/// All of these functions are called on the thread that called [`run`], which acts as the render
+/// thread. This is usually the main thread, as most windowing systems require their events to be
+/// pumped there.
+///
+/// [`run`]: fn.run.html
+pub trait Platform {
+    /// Events forwarded from the windowing system to the API thread.
+    type Event: Send + 'static;
+
+    /// Gets the platform data describing the window to render to.
+    fn platform_data(&self) -> Result<PlatformData, BgfxError>;
+
+    /// Gets the initializer used to initialize bgfx on the API thread. Defaults to
+    /// `Init::new()`.
+    fn init(&self) -> Init {
+        Init::new()
+    }
+
+    /// Pumps the window event queue, forwarding any events the API thread should know about.
+    ///
+    /// Returns `false` once the window has been closed.
+    fn pump_events(&mut self, events: &Sender<Self::Event>) -> bool;
+}
+
+/// Queue of events forwarded to the API thread by [`Platform::pump_events`].
+///
+/// [`Platform::pump_events`]: trait.Platform.html#tymethod.pump_events
+pub struct EventQueue<E> {
+    events: Receiver<E>,
+    closed: Cell<bool>,
+}
+
+impl<E> EventQueue<E> {
+
+    /// Gets the next pending event, if any. Returns instantly if there are no events to process.
+    pub fn poll(&self) -> Option<E> {
+        match self.events.try_recv() {
+            Ok(event) => Some(event),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.closed.set(true);
+                None
+            }
+        }
+    }
+
+    /// Returns `true` once [`poll`] has drained all events sent before the window was closed.
+    /// The API thread should exit when this happens.
+    ///
+    /// [`poll`]: #method.poll
+    #[inline]
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+}
+
+/// Flags the API thread as finished when dropped, even if it is unwinding from a panic.
+struct Finished(Arc<AtomicBool>);
+
+impl Drop for Finished {
+
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+}
+
+/// Runs a bgfx application, using the calling thread as the render thread.
+///
+/// The platform data is applied, after which a new thread is spawned to act as the API thread.
+/// That thread initializes bgfx using [`Platform::init`], and passes it on to `main` along with
+/// the queue of window events. Meanwhile, the calling thread pumps window events and renders
+/// frames, until either the window is closed or `main` returns.
+///
+/// Once the window has been closed, the event queue reports so through
+/// [`EventQueue::is_closed`], and `main` is expected to return. Rendering continues until bgfx
+/// has been shut down, so that no frame is left waiting on the render thread.
+///
+/// Returns the error from [`RenderThread::claim`] if the render thread can not be claimed. If
+/// initialization fails on the API thread, the error is returned. If `main` panics, the panic
+/// is resumed on the calling thread after bgfx has been shut down.
+///
+/// [`Platform::init`]: trait.Platform.html#method.init
+/// [`RenderThread::claim`]: ../struct.RenderThread.html#method.claim
+/// [`EventQueue::is_closed`]: struct.EventQueue.html#method.is_closed
+pub fn run<P, F>(mut platform: P, main: F) -> Result<(), BgfxError>
+    where P: Platform,
+          F: FnOnce(&Bgfx, EventQueue<P::Event>) + Send + 'static
+{
+    let render_thread = RenderThread::claim()?;
+    platform.platform_data()?.apply()?;
+
+    let mut init = platform.init();
+    let (event_tx, event_rx) = channel();
+    let finished = Arc::new(AtomicBool::new(false));
+    let api_finished = finished.clone();
+
+    let api_thread = thread::spawn(move || {
+        // Declared first so it is dropped last, after bgfx has been shut down.
+        let _finished = Finished(api_finished);
+        let bgfx = init.init()?;
+        main(&bgfx, EventQueue { events: event_rx, closed: Cell::new(false) });
+        Ok(())
+    });
+
+    // The event sender is dropped once the window is closed, which closes the event queue.
+    let mut events = Some(event_tx);
+
+    loop {
+        if events.as_ref().is_some_and(|events| !platform.pump_events(events)) {
+            events = None;
+        }
+
+        match render_thread.render_frame() {
+            // bgfx only waits so long for the API thread to submit a frame. Whether or not one was
+            // rendered, go back to pumping events so the window stays responsive.
+            RenderFrame::Render | RenderFrame::Timeout => {}
+
+            // The API thread is shutting bgfx down. Keep rendering until it is done, so that
+            // `bgfx_shutdown` is not left waiting on this thread.
+            RenderFrame::Exiting => {}
+
+            // bgfx is either not initialized yet, or already shut down. Don't spin on it.
+            RenderFrame::NoContext => {
+                if finished.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    drop(events);
+
+    match api_thread.join() {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
@@ -0,0 +1,751 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+//! Typed render state.
+//!
+//! `RenderState` describes the same state as the raw `STATE_*` flags, but can only express valid
+//! combinations of them. It compiles down to `StateFlags` through `to_flags`, and can be decoded
+//! back from them through `from_flags`, which makes for readable `Debug` output.
+//!
+//! `IndependentBlend` configures blending separately for each of up to 4 render targets.
+
+use super::{StateFlags, STATE_ALPHA_REF_MASK, STATE_ALPHA_REF_SHIFT, STATE_ALPHA_WRITE,
+            STATE_BLEND_EQUATION_MASK, STATE_BLEND_EQUATION_SHIFT, STATE_BLEND_INDEPENDENT,
+            STATE_BLEND_MASK, STATE_BLEND_SHIFT, STATE_CULL_MASK, STATE_DEPTH_TEST_MASK,
+            STATE_DEPTH_WRITE, STATE_MSAA, STATE_POINT_SIZE_MASK, STATE_POINT_SIZE_SHIFT,
+            STATE_PT_MASK, STATE_RGB_WRITE};
+
+/// Blend factor.
+#[repr(u64)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BlendFactor {
+    /// `0`
+    Zero = bgfx_sys::BGFX_STATE_BLEND_ZERO,
+
+    /// `1`
+    One = bgfx_sys::BGFX_STATE_BLEND_ONE,
+
+    /// Source color.
+    SrcColor = bgfx_sys::BGFX_STATE_BLEND_SRC_COLOR,
+
+    /// One minus the source color.
+    InvSrcColor = bgfx_sys::BGFX_STATE_BLEND_INV_SRC_COLOR,
+
+    /// Source alpha.
+    SrcAlpha = bgfx_sys::BGFX_STATE_BLEND_SRC_ALPHA,
+
+    /// One minus the source alpha.
+    InvSrcAlpha = bgfx_sys::BGFX_STATE_BLEND_INV_SRC_ALPHA,
+
+    /// Destination alpha.
+    DstAlpha = bgfx_sys::BGFX_STATE_BLEND_DST_ALPHA,
+
+    /// One minus the destination alpha.
+    InvDstAlpha = bgfx_sys::BGFX_STATE_BLEND_INV_DST_ALPHA,
+
+    /// Destination color.
+    DstColor = bgfx_sys::BGFX_STATE_BLEND_DST_COLOR,
+
+    /// One minus the destination color.
+    InvDstColor = bgfx_sys::BGFX_STATE_BLEND_INV_DST_COLOR,
+
+    /// The smaller of the source alpha and one minus the destination alpha.
+    SrcAlphaSat = bgfx_sys::BGFX_STATE_BLEND_SRC_ALPHA_SAT,
+
+    /// The blend factor color passed along with the state.
+    Factor = bgfx_sys::BGFX_STATE_BLEND_FACTOR,
+
+    /// One minus the blend factor color passed along with the state.
+    InvFactor = bgfx_sys::BGFX_STATE_BLEND_INV_FACTOR,
+}
+
+const BLEND_FACTORS: [BlendFactor; 13] = [BlendFactor::Zero,
+                                          BlendFactor::One,
+                                          BlendFactor::SrcColor,
+                                          BlendFactor::InvSrcColor,
+                                          BlendFactor::SrcAlpha,
+                                          BlendFactor::InvSrcAlpha,
+                                          BlendFactor::DstAlpha,
+                                          BlendFactor::InvDstAlpha,
+                                          BlendFactor::DstColor,
+                                          BlendFactor::InvDstColor,
+                                          BlendFactor::SrcAlphaSat,
+                                          BlendFactor::Factor,
+                                          BlendFactor::InvFactor];
+
+impl BlendFactor {
+
+    /// Returns the 4-bit value of the factor, as used within the blend state.
+    #[inline]
+    fn value(self) -> u64 {
+        self as u64 >> STATE_BLEND_SHIFT.bits()
+    }
+
+    fn from_value(value: u64) -> Option<BlendFactor> {
+        BLEND_FACTORS.iter().cloned().find(|factor| factor.value() == value)
+    }
+
+}
+
+/// Blend equation.
+#[repr(u64)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum BlendEquation {
+    /// `src + dst`
+    Add = bgfx_sys::BGFX_STATE_BLEND_EQUATION_ADD,
+
+    /// `src - dst`
+    Sub = bgfx_sys::BGFX_STATE_BLEND_EQUATION_SUB,
+
+    /// `dst - src`
+    RevSub = bgfx_sys::BGFX_STATE_BLEND_EQUATION_REVSUB,
+
+    /// `min(src, dst)`
+    Min = bgfx_sys::BGFX_STATE_BLEND_EQUATION_MIN,
+
+    /// `max(src, dst)`
+    Max = bgfx_sys::BGFX_STATE_BLEND_EQUATION_MAX,
+}
+
+const BLEND_EQUATIONS: [BlendEquation; 5] = [BlendEquation::Add,
+                                             BlendEquation::Sub,
+                                             BlendEquation::RevSub,
+                                             BlendEquation::Min,
+                                             BlendEquation::Max];
+
+impl BlendEquation {
+
+    /// Returns the 3-bit value of the equation, as used within the blend state.
+    #[inline]
+    fn value(self) -> u64 {
+        self as u64 >> STATE_BLEND_EQUATION_SHIFT.bits()
+    }
+
+    fn from_value(value: u64) -> Option<BlendEquation> {
+        BLEND_EQUATIONS.iter().cloned().find(|equation| equation.value() == value)
+    }
+
+}
+
+/// Depth test comparison. Fragments pass the test when the comparison between their depth and
+/// the depth in the depth buffer holds.
+#[repr(u64)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum DepthTest {
+    /// Passes if the fragment is nearer.
+    Less = bgfx_sys::BGFX_STATE_DEPTH_TEST_LESS,
+
+    /// Passes if the fragment is nearer or at the same depth.
+    LEqual = bgfx_sys::BGFX_STATE_DEPTH_TEST_LEQUAL,
+
+    /// Passes if the fragment is at the same depth.
+    Equal = bgfx_sys::BGFX_STATE_DEPTH_TEST_EQUAL,
+
+    /// Passes if the fragment is farther or at the same depth.
+    GEqual = bgfx_sys::BGFX_STATE_DEPTH_TEST_GEQUAL,
+
+    /// Passes if the fragment is farther.
+    Greater = bgfx_sys::BGFX_STATE_DEPTH_TEST_GREATER,
+
+    /// Passes if the fragment is not at the same depth.
+    NotEqual = bgfx_sys::BGFX_STATE_DEPTH_TEST_NOTEQUAL,
+
+    /// Never passes.
+    Never = bgfx_sys::BGFX_STATE_DEPTH_TEST_NEVER,
+
+    /// Always passes.
+    Always = bgfx_sys::BGFX_STATE_DEPTH_TEST_ALWAYS,
+}
+
+const DEPTH_TESTS: [DepthTest; 8] = [DepthTest::Less,
+                                     DepthTest::LEqual,
+                                     DepthTest::Equal,
+                                     DepthTest::GEqual,
+                                     DepthTest::Greater,
+                                     DepthTest::NotEqual,
+                                     DepthTest::Never,
+                                     DepthTest::Always];
+
+/// Face culling. The named winding order is the one that gets culled.
+#[repr(u64)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum CullMode {
+    /// Culls clockwise triangles.
+    Cw = bgfx_sys::BGFX_STATE_CULL_CW,
+
+    /// Culls counter-clockwise triangles.
+    Ccw = bgfx_sys::BGFX_STATE_CULL_CCW,
+}
+
+const CULL_MODES: [CullMode; 2] = [CullMode::Cw, CullMode::Ccw];
+
+/// Primitive type.
+#[repr(u64)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum PrimitiveType {
+    /// Triangle list.
+    TriList = bgfx_sys::BGFX_STATE_NONE,
+
+    /// Triangle strip.
+    TriStrip = bgfx_sys::BGFX_STATE_PT_TRISTRIP,
+
+    /// Line list.
+    Lines = bgfx_sys::BGFX_STATE_PT_LINES,
+
+    /// Line strip.
+    LineStrip = bgfx_sys::BGFX_STATE_PT_LINESTRIP,
+
+    /// Point list.
+    Points = bgfx_sys::BGFX_STATE_PT_POINTS,
+}
+
+const PRIMITIVE_TYPES: [PrimitiveType; 5] = [PrimitiveType::TriList,
+                                             PrimitiveType::TriStrip,
+                                             PrimitiveType::Lines,
+                                             PrimitiveType::LineStrip,
+                                             PrimitiveType::Points];
+
+/// Blend function and equation.
+///
+/// The blended color is `equation(src * src_factor, dst * dst_factor)`, where `src` is the
+/// fragment color and `dst` is the color already in the render target.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Blend {
+    /// Factor for the source color.
+    pub src_rgb: BlendFactor,
+
+    /// Factor for the destination color.
+    pub dst_rgb: BlendFactor,
+
+    /// Factor for the source alpha.
+    pub src_alpha: BlendFactor,
+
+    /// Factor for the destination alpha.
+    pub dst_alpha: BlendFactor,
+
+    /// Equation for the color.
+    pub equation_rgb: BlendEquation,
+
+    /// Equation for the alpha.
+    pub equation_alpha: BlendEquation,
+}
+
+impl Blend {
+
+    /// Blends color and alpha with the same factors, and adds the results.
+    #[inline]
+    pub fn new(src: BlendFactor, dst: BlendFactor) -> Blend {
+        Blend::separate(src, dst, src, dst)
+    }
+
+    /// Blends color and alpha with separate factors, and adds the results.
+    #[inline]
+    pub fn separate(src_rgb: BlendFactor,
+                    dst_rgb: BlendFactor,
+                    src_alpha: BlendFactor,
+                    dst_alpha: BlendFactor)
+                    -> Blend {
+        Blend {
+            src_rgb: src_rgb,
+            dst_rgb: dst_rgb,
+            src_alpha: src_alpha,
+            dst_alpha: dst_alpha,
+            equation_rgb: BlendEquation::Add,
+            equation_alpha: BlendEquation::Add,
+        }
+    }
+
+    /// Returns the blend with the given equation used for both color and alpha.
+    #[inline]
+    pub fn equation(self, equation: BlendEquation) -> Blend {
+        self.equation_separate(equation, equation)
+    }
+
+    /// Returns the blend with separate equations used for color and alpha.
+    #[inline]
+    pub fn equation_separate(self, rgb: BlendEquation, alpha: BlendEquation) -> Blend {
+        Blend { equation_rgb: rgb, equation_alpha: alpha, ..self }
+    }
+
+    fn to_bits(&self) -> u64 {
+        let func = self.src_rgb.value() | self.dst_rgb.value() << 4 |
+                   self.src_alpha.value() << 8 | self.dst_alpha.value() << 12;
+        let equation = self.equation_rgb.value() | self.equation_alpha.value() << 3;
+
+        func << STATE_BLEND_SHIFT.bits() | equation << STATE_BLEND_EQUATION_SHIFT.bits()
+    }
+
+    fn from_bits(bits: u64) -> Option<Option<Blend>> {
+        let func = (bits & STATE_BLEND_MASK.bits()) >> STATE_BLEND_SHIFT.bits();
+        let equation = (bits & STATE_BLEND_EQUATION_MASK.bits()) >>
+                       STATE_BLEND_EQUATION_SHIFT.bits();
+
+        if func == 0 {
+            return if equation == 0 { Some(None) } else { None };
+        }
+
+        Some(Some(Blend {
+            src_rgb: BlendFactor::from_value(func & 0xf)?,
+            dst_rgb: BlendFactor::from_value(func >> 4 & 0xf)?,
+            src_alpha: BlendFactor::from_value(func >> 8 & 0xf)?,
+            dst_alpha: BlendFactor::from_value(func >> 12 & 0xf)?,
+            equation_rgb: BlendEquation::from_value(equation & 0x7)?,
+            equation_alpha: BlendEquation::from_value(equation >> 3 & 0x7)?,
+        }))
+    }
+
+}
+
+/// Blend function and equation of a single render target, when blending independently. Color and
+/// alpha are blended the same way.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct TargetBlend {
+    /// Factor for the source color and alpha.
+    pub src: BlendFactor,
+
+    /// Factor for the destination color and alpha.
+    pub dst: BlendFactor,
+
+    /// Equation for the color and alpha.
+    pub equation: BlendEquation,
+}
+
+impl TargetBlend {
+
+    /// Blends with the given factors, and adds the results.
+    #[inline]
+    pub fn new(src: BlendFactor, dst: BlendFactor) -> TargetBlend {
+        TargetBlend { src: src, dst: dst, equation: BlendEquation::Add }
+    }
+
+    /// Returns the blend with the given equation.
+    #[inline]
+    pub fn equation(self, equation: BlendEquation) -> TargetBlend {
+        TargetBlend { equation: equation, ..self }
+    }
+
+    /// Returns the 11-bit value of the blend, as used within the `rgba` word.
+    fn to_bits(&self) -> u32 {
+        (self.src.value() | self.dst.value() << 4 | self.equation.value() << 8) as u32
+    }
+
+    fn from_bits(bits: u32) -> Option<Option<TargetBlend>> {
+        let bits = bits as u64;
+        if bits == 0 {
+            return Some(None);
+        }
+
+        Some(Some(TargetBlend {
+            src: BlendFactor::from_value(bits & 0xf)?,
+            dst: BlendFactor::from_value(bits >> 4 & 0xf)?,
+            equation: BlendEquation::from_value(bits >> 8 & 0x7)?,
+        }))
+    }
+
+}
+
+impl From<TargetBlend> for Blend {
+    #[inline]
+    fn from(blend: TargetBlend) -> Blend {
+        Blend::new(blend.src, blend.dst).equation(blend.equation)
+    }
+}
+
+/// Blending configured separately for each of up to 4 render targets.
+///
+/// The first render target is blended through the regular blend state, while the others are
+/// packed into the `rgba` word passed along with the state. `RenderState::independent_blend`
+/// takes care of both, for use with `Bgfx::set_render_state`.
+///
+/// ```
+/// # use bgfx::*;
+/// let mut blend = IndependentBlend::new();
+/// blend.target(0, None)
+///      .target(1, Some(TargetBlend::new(BlendFactor::One, BlendFactor::One)));
+///
+/// let state = *RenderState::opaque().independent_blend(Some(blend));
+/// assert_eq!(state.rgba(), Some(blend.rgba()));
+/// ```
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct IndependentBlend {
+    targets: [Option<TargetBlend>; 4],
+}
+
+impl IndependentBlend {
+
+    /// Creates a configuration with blending disabled for all render targets.
+    #[inline]
+    pub fn new() -> IndependentBlend {
+        IndependentBlend { targets: [None; 4] }
+    }
+
+    /// Decodes the blending of the given state flags and `rgba` word. Returns `None` if
+    /// `STATE_BLEND_INDEPENDENT` is not set, or if the blending can not be represented.
+    pub fn from_flags(flags: StateFlags, rgba: u32) -> Option<IndependentBlend> {
+        if !flags.contains(STATE_BLEND_INDEPENDENT) {
+            return None;
+        }
+
+        let first = match Blend::from_bits(flags.bits())? {
+            Some(blend) => {
+                let target = TargetBlend {
+                    src: blend.src_rgb,
+                    dst: blend.dst_rgb,
+                    equation: blend.equation_rgb,
+                };
+
+                // Separate alpha blending can not be represented by `TargetBlend`.
+                if Blend::from(target) != blend {
+                    return None;
+                }
+
+                Some(target)
+            }
+            None => None,
+        };
+
+        Some(IndependentBlend {
+            targets: [first,
+                      TargetBlend::from_bits(rgba & 0x7ff)?,
+                      TargetBlend::from_bits(rgba >> 11 & 0x7ff)?,
+                      TargetBlend::from_bits(rgba >> 22 & 0x7ff)?],
+        })
+    }
+
+    /// Returns the `rgba` word carrying the blending of the second to fourth render targets.
+    pub fn rgba(&self) -> u32 {
+        self.targets[1..]
+            .iter()
+            .enumerate()
+            .fold(0, |rgba, (i, target)| {
+                rgba | target.map_or(0, |target| target.to_bits()) << (i * 11)
+            })
+    }
+
+    /// Sets the blending of the render target at `index`, or `None` to disable blending for it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is 4 or greater, or if the fourth render target is set to use
+    /// `BlendEquation::Max`, which does not fit within the `rgba` word.
+    #[inline]
+    pub fn target(&mut self, index: usize, blend: Option<TargetBlend>) -> &mut Self {
+        assert!(index < self.targets.len(), "render target index out of bounds");
+        assert!(index != 3 || blend.map_or(true, |blend| blend.equation != BlendEquation::Max),
+                "the fourth render target can not use BlendEquation::Max");
+        self.targets[index] = blend;
+        self
+    }
+
+    /// Returns the blending of the render target at `index`, or `None` if it does not blend.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is 4 or greater.
+    #[inline]
+    pub fn target_blend(&self, index: usize) -> Option<TargetBlend> {
+        self.targets[index]
+    }
+
+    /// Compiles the state flags for the blending, including `STATE_BLEND_INDEPENDENT` and the
+    /// blending of the first render target.
+    pub fn to_flags(&self) -> StateFlags {
+        let first = self.targets[0].map_or(0, |target| Blend::from(target).to_bits());
+        StateFlags::from_bits_truncate(STATE_BLEND_INDEPENDENT.bits() | first)
+    }
+
+}
+
+impl Default for IndependentBlend {
+    #[inline]
+    fn default() -> IndependentBlend {
+        IndependentBlend::new()
+    }
+}
+
+/// Render state for draw calls.
+///
+/// Start from `new` or one of the presets, adjust it through the builder methods, and pass the
+/// state to `Bgfx::set_render_state`, or the result of `to_flags` to `Bgfx::set_state`:
+///
+/// ```
+/// # use bgfx::*;
+/// let flags = RenderState::alpha_blend().cull(None).to_flags();
+/// assert_eq!(RenderState::from_flags(flags), Some(*RenderState::alpha_blend().cull(None)));
+/// ```
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct RenderState {
+    rgb_write: bool,
+    alpha_write: bool,
+    depth_write: bool,
+    depth_test: Option<DepthTest>,
+    blend: Option<Blend>,
+    blend_color: Option<u32>,
+    independent_blend: Option<IndependentBlend>,
+    cull: Option<CullMode>,
+    alpha_ref: u8,
+    primitive: PrimitiveType,
+    point_size: u8,
+    msaa: bool,
+}
+
+impl RenderState {
+
+    /// Creates a state with nothing enabled, matching `STATE_NONE`.
+    pub fn new() -> RenderState {
+        RenderState {
+            rgb_write: false,
+            alpha_write: false,
+            depth_write: false,
+            depth_test: None,
+            blend: None,
+            blend_color: None,
+            independent_blend: None,
+            cull: None,
+            alpha_ref: 0,
+            primitive: PrimitiveType::TriList,
+            point_size: 0,
+            msaa: false,
+        }
+    }
+
+    /// Opaque geometry: writes color, alpha and depth, tests depth with `DepthTest::Less`, culls
+    /// clockwise triangles and enables MSAA. Matches `STATE_DEFAULT`.
+    pub fn opaque() -> RenderState {
+        RenderState {
+            rgb_write: true,
+            alpha_write: true,
+            depth_write: true,
+            depth_test: Some(DepthTest::Less),
+            cull: Some(CullMode::Cw),
+            msaa: true,
+            ..RenderState::new()
+        }
+    }
+
+    /// Alpha blended geometry: like `opaque`, but blends with `SrcAlpha, InvSrcAlpha` and does
+    /// not write depth.
+    pub fn alpha_blend() -> RenderState {
+        RenderState::translucent(Blend::new(BlendFactor::SrcAlpha, BlendFactor::InvSrcAlpha))
+    }
+
+    /// Additive geometry: like `opaque`, but blends with `One, One` and does not write depth.
+    pub fn additive() -> RenderState {
+        RenderState::translucent(Blend::new(BlendFactor::One, BlendFactor::One))
+    }
+
+    /// Geometry with premultiplied alpha: like `opaque`, but blends with `One, InvSrcAlpha` and
+    /// does not write depth.
+    pub fn premultiplied() -> RenderState {
+        RenderState::translucent(Blend::new(BlendFactor::One, BlendFactor::InvSrcAlpha))
+    }
+
+    fn translucent(blend: Blend) -> RenderState {
+        RenderState { depth_write: false, blend: Some(blend), ..RenderState::opaque() }
+    }
+
+    /// Sets the alpha reference value used for alpha testing.
+    #[inline]
+    pub fn alpha_ref(&mut self, alpha_ref: u8) -> &mut Self {
+        self.alpha_ref = alpha_ref;
+        self
+    }
+
+    /// Sets whether to write to the alpha channel.
+    #[inline]
+    pub fn alpha_write(&mut self, enabled: bool) -> &mut Self {
+        self.alpha_write = enabled;
+        self
+    }
+
+    /// Sets the blending to use for all render targets, or `None` to disable blending. Replaces
+    /// any independent blending.
+    #[inline]
+    pub fn blend(&mut self, blend: Option<Blend>) -> &mut Self {
+        self.blend = blend;
+        self.independent_blend = None;
+        self
+    }
+
+    /// Sets the color used by the `BlendFactor::Factor` and `BlendFactor::InvFactor` blend factors,
+    /// as `0xRRGGBBAA`. Unused with independent blending, which needs the `rgba` word for itself.
+    #[inline]
+    pub fn blend_color(&mut self, rgba: u32) -> &mut Self {
+        self.blend_color = Some(rgba);
+        self
+    }
+
+    /// Sets the face culling to use, or `None` to disable culling.
+    #[inline]
+    pub fn cull(&mut self, cull: Option<CullMode>) -> &mut Self {
+        self.cull = cull;
+        self
+    }
+
+    /// Sets the depth test to use, or `None` to disable depth testing.
+    #[inline]
+    pub fn depth_test(&mut self, depth_test: Option<DepthTest>) -> &mut Self {
+        self.depth_test = depth_test;
+        self
+    }
+
+    /// Sets whether to write to the depth buffer.
+    #[inline]
+    pub fn depth_write(&mut self, enabled: bool) -> &mut Self {
+        self.depth_write = enabled;
+        self
+    }
+
+    /// Decodes state flags. Returns `None` if the flags contain values `RenderState` can not
+    /// represent, such as reserved bits or invalid blend factors. Flags with independent
+    /// blending must be decoded through `from_flags_rgba`.
+    pub fn from_flags(flags: StateFlags) -> Option<RenderState> {
+        RenderState::decode(flags, None)
+    }
+
+    /// Decodes state flags along with the `rgba` word passed with them. The `rgba` word holds the
+    /// independent blending if `STATE_BLEND_INDEPENDENT` is set, and the blend color otherwise.
+    pub fn from_flags_rgba(flags: StateFlags, rgba: u32) -> Option<RenderState> {
+        RenderState::decode(flags, Some(rgba))
+    }
+
+    fn decode(flags: StateFlags, rgba: Option<u32>) -> Option<RenderState> {
+        let bits = flags.bits();
+
+        let (blend, blend_color, independent_blend) = if flags.contains(STATE_BLEND_INDEPENDENT) {
+            (None, None, Some(IndependentBlend::from_flags(flags, rgba?)?))
+        } else {
+            (Blend::from_bits(bits)?, rgba, None)
+        };
+
+        let depth_test = match bits & STATE_DEPTH_TEST_MASK.bits() {
+            0 => None,
+            value => Some(DEPTH_TESTS.iter().cloned().find(|test| *test as u64 == value)?),
+        };
+
+        let cull = match bits & STATE_CULL_MASK.bits() {
+            0 => None,
+            value => Some(CULL_MODES.iter().cloned().find(|cull| *cull as u64 == value)?),
+        };
+
+        let primitive = bits & STATE_PT_MASK.bits();
+        let primitive = PRIMITIVE_TYPES.iter().cloned().find(|pt| *pt as u64 == primitive)?;
+
+        let state = RenderState {
+            rgb_write: flags.contains(STATE_RGB_WRITE),
+            alpha_write: flags.contains(STATE_ALPHA_WRITE),
+            depth_write: flags.contains(STATE_DEPTH_WRITE),
+            depth_test: depth_test,
+            blend: blend,
+            blend_color: blend_color,
+            independent_blend: independent_blend,
+            cull: cull,
+            alpha_ref: ((bits & STATE_ALPHA_REF_MASK.bits()) >> STATE_ALPHA_REF_SHIFT.bits()) as u8,
+            primitive: primitive,
+            point_size: ((bits & STATE_POINT_SIZE_MASK.bits()) >>
+                         STATE_POINT_SIZE_SHIFT.bits()) as u8,
+            msaa: flags.contains(STATE_MSAA),
+        };
+
+        // Anything not covered by the fields above, such as the reserved bits or an `rgba` word
+        // that does not decode to the same independent blending, can not be represented.
+        if state.to_flags() == flags && state.rgba() == rgba {
+            Some(state)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the blending to use for each render target, or `None` to disable blending. Replaces
+    /// any blending set through `blend`.
+    #[inline]
+    pub fn independent_blend(&mut self, blend: Option<IndependentBlend>) -> &mut Self {
+        self.blend = None;
+        self.independent_blend = blend;
+        self
+    }
+
+    /// Sets whether to enable multisampling.
+    #[inline]
+    pub fn msaa(&mut self, enabled: bool) -> &mut Self {
+        self.msaa = enabled;
+        self
+    }
+
+    /// Sets the size of points, in pixels.
+    #[inline]
+    pub fn point_size(&mut self, size: u8) -> &mut Self {
+        self.point_size = size;
+        self
+    }
+
+    /// Sets the type of primitives to render.
+    #[inline]
+    pub fn primitive(&mut self, primitive: PrimitiveType) -> &mut Self {
+        self.primitive = primitive;
+        self
+    }
+
+    /// Returns the `rgba` word to pass along with the state flags: the blending of the other
+    /// render targets if the state uses independent blending, and the blend color otherwise.
+    #[inline]
+    pub fn rgba(&self) -> Option<u32> {
+        match self.independent_blend {
+            Some(blend) => Some(blend.rgba()),
+            None => self.blend_color,
+        }
+    }
+
+    /// Sets whether to write to the color channels.
+    #[inline]
+    pub fn rgb_write(&mut self, enabled: bool) -> &mut Self {
+        self.rgb_write = enabled;
+        self
+    }
+
+    /// Compiles the state to state flags.
+    pub fn to_flags(&self) -> StateFlags {
+        let mut bits = 0;
+
+        if self.rgb_write {
+            bits |= STATE_RGB_WRITE.bits();
+        }
+
+        if self.alpha_write {
+            bits |= STATE_ALPHA_WRITE.bits();
+        }
+
+        if self.depth_write {
+            bits |= STATE_DEPTH_WRITE.bits();
+        }
+
+        if self.msaa {
+            bits |= STATE_MSAA.bits();
+        }
+
+        bits |= self.depth_test.map_or(0, |depth_test| depth_test as u64);
+        bits |= self.blend.map_or(0, |blend| blend.to_bits());
+        bits |= self.independent_blend.map_or(0, |blend| blend.to_flags().bits());
+        bits |= self.cull.map_or(0, |cull| cull as u64);
+        bits |= (self.alpha_ref as u64) << STATE_ALPHA_REF_SHIFT.bits();
+        bits |= self.primitive as u64;
+        bits |= (self.point_size as u64) << STATE_POINT_SIZE_SHIFT.bits();
+
+        StateFlags::from_bits_truncate(bits)
+    }
+
+}
+
+impl Default for RenderState {
+    #[inline]
+    fn default() -> RenderState {
+        RenderState::opaque()
+    }
+}
+
+impl From<RenderState> for StateFlags {
+    #[inline]
+    fn from(state: RenderState) -> StateFlags {
+        state.to_flags()
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+//! Mesh topology helpers.
+//!
+//! These run entirely on the CPU, but allocate their scratch memory through bgfx's allocator,
+//! which is why they take the [`Bgfx`] object.
+//!
+//! [`Bgfx`]: ../struct.Bgfx.html
+
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+
+use super::{assert_vertex_stride, Attrib, AttribType, Bgfx, Index, Pod, Vertex};
+
+/// Topology conversion.
+#[repr(i32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TopologyConvert {
+    /// Flips the winding order of a triangle list.
+    TriListFlipWinding =
+        bgfx_sys::bgfx_topology_convert::BGFX_TOPOLOGY_CONVERT_TRI_LIST_FLIP_WINDING as i32,
+
+    /// Converts a triangle list to a list of its unique edges.
+    TriListToLineList =
+        bgfx_sys::bgfx_topology_convert::BGFX_TOPOLOGY_CONVERT_TRI_LIST_TO_LINE_LIST as i32,
+
+    /// Converts a triangle strip to a triangle list.
+    TriStripToTriList =
+        bgfx_sys::bgfx_topology_convert::BGFX_TOPOLOGY_CONVERT_TRI_STRIP_TO_TRI_LIST as i32,
+
+    /// Converts a line strip to a line list.
+    LineStripToLineList =
+        bgfx_sys::bgfx_topology_convert::BGFX_TOPOLOGY_CONVERT_LINE_STRIP_TO_LINE_LIST as i32,
+}
+
+impl TopologyConvert {
+
+    /// Number of indices making up the first primitive of the input.
+    fn min_indices(self) -> usize {
+        match self {
+            TopologyConvert::TriListFlipWinding |
+            TopologyConvert::TriListToLineList |
+            TopologyConvert::TriStripToTriList => 3,
+            TopologyConvert::LineStripToLineList => 2,
+        }
+    }
+
+    /// Whether the input is a list of whole triangles.
+    fn is_tri_list(self) -> bool {
+        match self {
+            TopologyConvert::TriListFlipWinding | TopologyConvert::TriListToLineList => true,
+            TopologyConvert::TriStripToTriList | TopologyConvert::LineStripToLineList => false,
+        }
+    }
+
+}
+
+/// Triangle sort order.
+///
+/// `Direction` sorts triangles along the view direction, while `Distance` sorts them by their
+/// distance from the view position. `Min`, `Avg` and `Max` select which vertex of each triangle
+/// is used as its sort key.
+#[repr(i32)]
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum TopologySort {
+    /// Front to back along the view direction, by the nearest vertex.
+    DirectionFrontToBackMin =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DIRECTION_FRONT_TO_BACK_MIN as i32,
+
+    /// Front to back along the view direction, by the average of the vertices.
+    DirectionFrontToBackAvg =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DIRECTION_FRONT_TO_BACK_AVG as i32,
+
+    /// Front to back along the view direction, by the farthest vertex.
+    DirectionFrontToBackMax =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DIRECTION_FRONT_TO_BACK_MAX as i32,
+
+    /// Back to front along the view direction, by the nearest vertex.
+    DirectionBackToFrontMin =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DIRECTION_BACK_TO_FRONT_MIN as i32,
+
+    /// Back to front along the view direction, by the average of the vertices.
+    DirectionBackToFrontAvg =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DIRECTION_BACK_TO_FRONT_AVG as i32,
+
+    /// Back to front along the view direction, by the farthest vertex.
+    DirectionBackToFrontMax =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DIRECTION_BACK_TO_FRONT_MAX as i32,
+
+    /// Front to back by distance from the view position, by the nearest vertex.
+    DistanceFrontToBackMin =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DISTANCE_FRONT_TO_BACK_MIN as i32,
+
+    /// Front to back by distance from the view position, by the average of the vertices.
+    DistanceFrontToBackAvg =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DISTANCE_FRONT_TO_BACK_AVG as i32,
+
+    /// Front to back by distance from the view position, by the farthest vertex.
+    DistanceFrontToBackMax =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DISTANCE_FRONT_TO_BACK_MAX as i32,
+
+    /// Back to front by distance from the view position, by the nearest vertex.
+    DistanceBackToFrontMin =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DISTANCE_BACK_TO_FRONT_MIN as i32,
+
+    /// Back to front by distance from the view position, by the average of the vertices.
+    DistanceBackToFrontAvg =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DISTANCE_BACK_TO_FRONT_AVG as i32,
+
+    /// Back to front by distance from the view position, by the farthest vertex.
+    DistanceBackToFrontMax =
+        bgfx_sys::bgfx_topology_sort::BGFX_TOPOLOGY_SORT_DISTANCE_BACK_TO_FRONT_MAX as i32,
+}
+
+/// Converts the topology of a list of indices. Returns no indices if the input is too short to
+/// hold a single primitive.
+///
+/// Trailing indices that do not make up a whole triangle are ignored when converting a triangle
+/// list.
+pub fn convert<I: Index>(_bgfx: &Bgfx, conversion: TopologyConvert, indices: &[I]) -> Vec<I> {
+    // bgfx computes the output size of strips without checking for underflow.
+    if indices.len() < conversion.min_indices() {
+        return Vec::new();
+    }
+
+    // bgfx steps through triangle lists three indices at a time, past the end of the input.
+    let indices = if conversion.is_tri_list() {
+        &indices[..indices.len() - indices.len() % 3]
+    } else {
+        indices
+    };
+
+    let index32 = mem::size_of::<I>() == 4;
+    let conversion = unsafe { mem::transmute(conversion) };
+
+    unsafe {
+        // Without a destination, bgfx returns the number of indices the conversion produces.
+        let num = bgfx_sys::bgfx_topology_convert(conversion,
+                                                  ptr::null_mut(),
+                                                  0,
+                                                  indices.as_ptr() as *const c_void,
+                                                  indices.len() as u32,
+                                                  index32);
+
+        let mut converted = Vec::with_capacity(num as usize);
+        let num = bgfx_sys::bgfx_topology_convert(conversion,
+                                                  converted.as_mut_ptr() as *mut c_void,
+                                                  (num as usize * mem::size_of::<I>()) as u32,
+                                                  indices.as_ptr() as *const c_void,
+                                                  indices.len() as u32,
+                                                  index32);
+        converted.set_len(num as usize);
+        converted
+    }
+}
+
+/// Sorts the triangles of a triangle list relative to a view, for instance to render
+/// transparent meshes back to front.
+///
+/// `dir` is the view direction and `pos` the view position. The triangles are sorted by the
+/// `Position` attribute of `V`, which must consist of at least three floats.
+///
+/// # Panics
+///
+/// Panics if the indices do not make up whole triangles, if the declaration of `V` does not
+/// match its size, if the vertices have no float position, or if an index is out of bounds.
+pub fn sort_tri_list<I, V>(_bgfx: &Bgfx,
+                           sort: TopologySort,
+                           indices: &[I],
+                           dir: [f32; 3],
+                           pos: [f32; 3],
+                           vertices: &[V])
+                           -> Vec<I>
+    where I: Index,
+          V: Vertex + Pod
+{
+    assert!(indices.len() % 3 == 0, "indices do not make up whole triangles");
+
+    let decl = V::decl();
+    assert_vertex_stride::<V>(decl.stride());
+    match decl.attribute(Attrib::Position) {
+        Some((count, AttribType::Float, _)) if count >= 3 => {}
+        _ => panic!("vertices have no float position"),
+    }
+
+    assert!(indices.iter().all(|&index| (index.into() as usize) < vertices.len()),
+            "index out of bounds");
+
+    // bgfx reads the positions directly, so point it at the first one.
+    let offset = decl.offset(Attrib::Position).unwrap() as usize;
+    let positions = unsafe { (vertices.as_ptr() as *const u8).add(offset) };
+    let mut sorted = Vec::with_capacity(indices.len());
+
+    unsafe {
+        bgfx_sys::bgfx_topology_sort_tri_list(mem::transmute(sort),
+                                              sorted.as_mut_ptr() as *mut c_void,
+                                              mem::size_of_val(indices) as u32,
+                                              dir.as_ptr(),
+                                              pos.as_ptr(),
+                                              positions as *const c_void,
+                                              decl.stride() as u32,
+                                              indices.as_ptr() as *const c_void,
+                                              indices.len() as u32,
+                                              mem::size_of::<I>() == 4);
+        sorted.set_len(indices.len());
+    }
+
+    sorted
+}
+
+/// Finds vertices with identical positions, within `epsilon`, so they can be merged.
+///
+/// Returns a remapping table, holding the index of the first identical vertex for each vertex,
+/// along with the number of unique vertices.
+///
+/// # Panics
+///
+/// Panics if there are more than 65535 vertices, or if the declaration of `V` does not match its
+/// size.
+pub fn weld_vertices<V>(_bgfx: &Bgfx, vertices: &[V], epsilon: f32) -> (Vec<u16>, usize)
+    where V: Vertex + Pod
+{
+    assert!(vertices.len() <= u16::max_value() as usize, "too many vertices");
+
+    let decl = V::decl();
+    assert_vertex_stride::<V>(decl.stride());
+    let mut remap = vec![0; vertices.len()];
+    let unique = unsafe {
+        bgfx_sys::bgfx_weld_vertices(remap.as_mut_ptr(),
+                                     &decl.decl,
+                                     vertices.as_ptr() as *const c_void,
+                                     vertices.len() as u16,
+                                     epsilon)
+    };
+
+    (remap, unique as usize)
+}
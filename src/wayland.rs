@@ -0,0 +1,73 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+//! EGL windows for Wayland surfaces.
+//!
+//! bgfx renders to Wayland through EGL, which takes a `wl_egl_window` rather than the `wl_surface`
+//! that window handles provide. `PlatformData::from_window_handle` creates one for the surface,
+//! which is then kept alive and sized along with bgfx.
+
+use std::os::raw::{c_int, c_void};
+use std::sync::Mutex;
+
+extern "C" {
+    fn wl_egl_window_create(surface: *mut c_void, width: c_int, height: c_int) -> *mut c_void;
+    fn wl_egl_window_destroy(window: *mut c_void);
+    fn wl_egl_window_resize(window: *mut c_void,
+                            width: c_int,
+                            height: c_int,
+                            dx: c_int,
+                            dy: c_int);
+}
+
+/// EGL window created for a Wayland surface, destroyed when dropped.
+pub struct EglWindow(*mut c_void);
+
+// The window is only ever used through the `PlatformData` owning it, or through `CURRENT`.
+unsafe impl Send for EglWindow {}
+
+impl EglWindow {
+
+    /// Creates an EGL window for the surface. It is sized once bgfx is initialized.
+    pub fn new(surface: *mut c_void) -> Option<EglWindow> {
+        let window = unsafe { wl_egl_window_create(surface, 1, 1) };
+        if window.is_null() {
+            None
+        } else {
+            Some(EglWindow(window))
+        }
+    }
+
+    /// Gets the `wl_egl_window` to hand to bgfx.
+    #[inline]
+    pub fn as_ptr(&self) -> *mut c_void {
+        self.0
+    }
+
+}
+
+impl Drop for EglWindow {
+
+    fn drop(&mut self) {
+        unsafe { wl_egl_window_destroy(self.0) }
+    }
+
+}
+
+/// EGL window created by the crate that bgfx was last pointed at through `PlatformData::apply`.
+/// It is kept for the rest of the process, as bgfx may be initialized anew with the same
+/// platform data.
+static CURRENT: Mutex<Option<EglWindow>> = Mutex::new(None);
+
+/// Makes `window` the EGL window bgfx renders to, destroying the previous one. Windows set by the
+/// user through `PlatformData::wayland_egl_window` are passed as `None`, as the user owns them.
+pub fn install(window: Option<EglWindow>) {
+    *CURRENT.lock().unwrap_or_else(|err| err.into_inner()) = window;
+}
+
+/// Resizes the installed EGL window, if any, to match the bgfx back buffer.
+pub fn resize(width: u16, height: u16) {
+    if let Some(ref window) = *CURRENT.lock().unwrap_or_else(|err| err.into_inner()) {
+        unsafe { wl_egl_window_resize(window.0, width as c_int, height as c_int, 0, 0) }
+    }
+}
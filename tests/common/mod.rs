@@ -0,0 +1,198 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+#![allow(dead_code)]
+
+extern crate bgfx_sys;
+
+use bgfx::*;
+
+use self::bgfx_sys::{bgfx_callback_interface_t, bgfx_callback_vtbl, bgfx_fatal_t,
+                     bgfx_texture_format_t, va_list};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_void};
+use std::sync::Mutex;
+
+/// bgfx can only be initialized once at a time, while the test harness runs tests in parallel.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Errors and leaks reported by bgfx through the callback installed by `with_bgfx`.
+static REPORTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+static CALLBACKS: bgfx_callback_vtbl = bgfx_callback_vtbl {
+    fatal: Some(fatal),
+    trace_vargs: Some(trace_vargs),
+    cache_read_size: Some(cache_read_size),
+    cache_read: Some(cache_read),
+    cache_write: Some(cache_write),
+    screen_shot: Some(screen_shot),
+    capture_begin: Some(capture_begin),
+    capture_end: Some(capture_end),
+    capture_frame: Some(capture_frame),
+};
+
+fn report(message: String) {
+    REPORTS.lock().unwrap_or_else(|e| e.into_inner()).push(message);
+}
+
+unsafe extern "C" fn fatal(_: *mut bgfx_callback_interface_t,
+                           code: bgfx_fatal_t,
+                           message: *const c_char) {
+    report(format!("{:?}: {}", code, CStr::from_ptr(message).to_string_lossy()));
+}
+
+unsafe extern "C" fn trace_vargs(_: *mut bgfx_callback_interface_t,
+                                 _: *const c_char,
+                                 _: u16,
+                                 format: *const c_char,
+                                 _: va_list) {
+    // Debug builds of bgfx warn about every handle still alive when it is shut down. The handle
+    // type is part of the format string itself.
+    let format = CStr::from_ptr(format).to_string_lossy();
+    if format.contains("LEAK") {
+        report(format.into_owned());
+    }
+}
+
+unsafe extern "C" fn cache_read_size(_: *mut bgfx_callback_interface_t, _: u64) -> u32 {
+    0
+}
+
+unsafe extern "C" fn cache_read(_: *mut bgfx_callback_interface_t,
+                                _: u64,
+                                _: *mut c_void,
+                                _: u32)
+                                -> bool {
+    false
+}
+
+unsafe extern "C" fn cache_write(_: *mut bgfx_callback_interface_t,
+                                 _: u64,
+                                 _: *const c_void,
+                                 _: u32) {
+}
+
+unsafe extern "C" fn screen_shot(_: *mut bgfx_callback_interface_t,
+                                 _: *const c_char,
+                                 _: u32,
+                                 _: u32,
+                                 _: u32,
+                                 _: *const c_void,
+                                 _: u32,
+                                 _: bool) {
+}
+
+unsafe extern "C" fn capture_begin(_: *mut bgfx_callback_interface_t,
+                                   _: u32,
+                                   _: u32,
+                                   _: u32,
+                                   _: bgfx_texture_format_t,
+                                   _: bool) {
+}
+
+unsafe extern "C" fn capture_end(_: *mut bgfx_callback_interface_t) {}
+
+unsafe extern "C" fn capture_frame(_: *mut bgfx_callback_interface_t, _: *const c_void, _: u32) {}
+
+/// Vertex type matching the declaration built by `pos_color_decl()`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct PosColorVertex {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub abgr: u32,
+}
+
+unsafe impl Pod for PosColorVertex {}
+
+impl Vertex for PosColorVertex {
+    fn decl() -> VertexDecl {
+        pos_color_decl()
+    }
+}
+
+/// Vertex whose hand-written declaration is wider than the struct itself.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct WideVertex {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+unsafe impl Pod for WideVertex {}
+
+impl Vertex for WideVertex {
+    fn decl() -> VertexDecl {
+        VertexDecl::new(None)
+            .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+            .skip(4)
+            .end()
+    }
+}
+
+#[cfg_attr(rustfmt, rustfmt_skip)]
+pub static QUAD_VERTICES: [PosColorVertex; 4] = [
+    PosColorVertex { x: -1.0, y:  1.0, z: 0.0, abgr: 0xff0000ff },
+    PosColorVertex { x:  1.0, y:  1.0, z: 0.0, abgr: 0xff00ff00 },
+    PosColorVertex { x: -1.0, y: -1.0, z: 0.0, abgr: 0xffff0000 },
+    PosColorVertex { x:  1.0, y: -1.0, z: 0.0, abgr: 0xffffffff },
+];
+
+pub static QUAD_INDICES: [u16; 6] = [0, 1, 2, 1, 3, 2];
+
+pub const VS_CUBES: &'static [u8] = include_bytes!("../../examples/assets/01-cubes/OpenGL/vs_cubes.bin");
+pub const FS_CUBES: &'static [u8] = include_bytes!("../../examples/assets/01-cubes/OpenGL/fs_cubes.bin");
+
+/// Initializes a headless bgfx instance for the duration of `test`, making sure no other test
+/// uses bgfx at the same time.
+///
+/// Fails the test if bgfx reports an error, or, in debug builds of bgfx, if any handle is still
+/// alive once bgfx has been shut down, such as one that never made it through the destroy queue.
+pub fn with_bgfx<F>(test: F)
+    where F: FnOnce(&Bgfx)
+{
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    REPORTS.lock().unwrap_or_else(|e| e.into_inner()).clear();
+
+    // Declared before bgfx, so it outlives it.
+    let mut callback = bgfx_callback_interface_t { vtbl: &CALLBACKS };
+
+    {
+        let bgfx = unsafe {
+            Init::headless()
+                .resolution(320, 240)
+                .callback(&mut callback)
+                .init()
+                .expect("Failed to initialize bgfx")
+        };
+        test(&bgfx);
+    }
+
+    let reports = REPORTS.lock().unwrap_or_else(|e| e.into_inner());
+    assert!(reports.is_empty(), "bgfx reported errors: {:?}", *reports);
+}
+
+/// Builds the vertex declaration for `PosColorVertex`.
+pub fn pos_color_decl() -> VertexDecl {
+    VertexDecl::new(None)
+        .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+        .add(Attrib::Color0, 4, AttribType::Uint8, ATTRIB_NORMALIZED)
+        .end()
+}
+
+/// Creates the program used by the cubes example.
+pub fn load_program<'a>(bgfx: &'a Bgfx) -> Program<'a> {
+    let vsh = Shader::new(Memory::copy(bgfx, VS_CUBES));
+    let fsh = Shader::new(Memory::copy(bgfx, FS_CUBES));
+    Program::new(vsh, fsh)
+}
+
+/// Sets up view 0 to cover the whole backbuffer.
+pub fn setup_view(bgfx: &Bgfx) {
+    let identity = [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+    bgfx.set_view_clear(0, CLEAR_COLOR | CLEAR_DEPTH, 0x303030ff, 1.0, 0);
+    bgfx.set_view_rect(0, 0, 0, 320, 240);
+    bgfx.set_view_transform(0, &identity, &identity);
+}
@@ -0,0 +1,87 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+extern crate bgfx_derive;
+
+mod common;
+
+use bgfx::*;
+use bgfx_derive::Vertex;
+use common::{QUAD_INDICES, QUAD_VERTICES};
+use std::mem;
+
+#[derive(Vertex, Copy, Clone)]
+#[repr(C)]
+struct PosColorVertex {
+    #[bgfx(attrib = "Position")]
+    pos: [f32; 3],
+    #[bgfx(attrib = "Color0", normalized)]
+    abgr: u32,
+}
+
+#[derive(Vertex, Copy, Clone)]
+#[repr(C)]
+struct PaddedVertex(#[bgfx(attrib = "Position")] [f32; 2],
+                    #[bgfx(skip)] u32,
+                    #[bgfx(attrib = "TexCoord0", ty = "Half")] [u16; 2]);
+
+#[derive(Vertex, Copy, Clone)]
+#[repr(C, packed)]
+struct PackedVertex {
+    #[bgfx(attrib = "Normal", ty = "Uint10", count = 3, normalized)]
+    _normal: u32,
+    #[bgfx(attrib = "Indices")]
+    _indices: [u8; 4],
+    #[bgfx(attrib = "Weight")]
+    _weight: f32,
+}
+
+#[test]
+fn derived_decls() {
+    assert_eq!(PosColorVertex::decl(), common::pos_color_decl());
+    assert_eq!(PosColorVertex::decl().stride() as usize, mem::size_of::<PosColorVertex>());
+
+    let padded = PaddedVertex::decl();
+    assert_eq!(padded.stride(), 16);
+    assert_eq!(padded.offset(Attrib::Position), Some(0));
+    assert_eq!(padded.offset(Attrib::TexCoord0), Some(12));
+    assert_eq!(padded.attribute(Attrib::TexCoord0), Some((2, AttribType::Half, ATTRIB_NONE)));
+
+    let packed = PackedVertex::decl();
+    assert_eq!(packed.stride(), 12);
+    assert_eq!(packed.attribute(Attrib::Normal),
+               Some((3, AttribType::Uint10, ATTRIB_NORMALIZED)));
+    assert_eq!(packed.attribute(Attrib::Indices), Some((4, AttribType::Uint8, ATTRIB_NONE)));
+    assert!(!packed.has(Attrib::Position));
+}
+
+#[test]
+fn derived_vertex_buffer() {
+    static VERTICES: [PosColorVertex; 3] = [
+        PosColorVertex { pos: [-1.0, -1.0, 0.0], abgr: 0xff0000ff },
+        PosColorVertex { pos: [1.0, -1.0, 0.0], abgr: 0xff00ff00 },
+        PosColorVertex { pos: [0.0, 1.0, 0.0], abgr: 0xffff0000 },
+    ];
+
+    common::with_bgfx(|bgfx| {
+        let program = common::load_program(bgfx);
+        let vb = VertexBuffer::new(Memory::reference(bgfx, &VERTICES),
+                                   &PosColorVertex::decl(),
+                                   BUFFER_NONE);
+
+        // The derived decl matches the one `common` builds by hand.
+        let common_vb = VertexBuffer::new(Memory::reference(bgfx, &QUAD_VERTICES),
+                                          &PosColorVertex::decl(),
+                                          BUFFER_NONE);
+        let ib = IndexBuffer::new(Memory::reference(bgfx, &QUAD_INDICES), BUFFER_NONE);
+
+        common::setup_view(bgfx);
+        bgfx.set_vertex_buffer(0, &vb);
+        bgfx.submit(0, &program, false);
+        bgfx.set_vertex_buffer(0, &common_vb);
+        bgfx.set_index_buffer(&ib);
+        bgfx.submit(0, &program, false);
+        bgfx.frame(false);
+    });
+}
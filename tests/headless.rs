@@ -0,0 +1,54 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+mod common;
+
+use bgfx::*;
+
+#[test]
+fn headless_frames() {
+    common::with_bgfx(|bgfx| {
+        assert_eq!(bgfx.get_renderer_type(), RendererType::Noop);
+        common::setup_view(bgfx);
+
+        let first = bgfx.frame(false);
+        for i in 1..4 {
+            bgfx.touch(0);
+            assert_eq!(bgfx.frame(false), first + i);
+        }
+    });
+}
+
+#[test]
+fn reinitialize() {
+    for _ in 0..3 {
+        common::with_bgfx(|bgfx| {
+            bgfx.touch(0);
+            bgfx.frame(false);
+        });
+    }
+}
+
+#[test]
+fn debug_text() {
+    common::with_bgfx(|bgfx| {
+        bgfx.set_debug(DEBUG_TEXT);
+        bgfx.dbg_text_clear(None, None);
+        bgfx.dbg_text_print(0, 1, 0x4f, "tests/headless.rs");
+        bgfx.dbg_text_image(0, 2, 2, 1, &[0x41, 0x0f, 0x42, 0x0f], 4);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn already_initialized() {
+    common::with_bgfx(|_| {
+        match Init::headless().init() {
+            Err(BgfxError::AlreadyInitialized) => {}
+            Err(err) => panic!("Unexpected error: {:?}", err),
+            Ok(_) => panic!("bgfx was initialized twice"),
+        }
+    });
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+extern crate raw_window_handle;
+
+use bgfx::*;
+use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle,
+                        RawWindowHandle, WaylandDisplayHandle, WaylandWindowHandle,
+                        WebDisplayHandle, WebWindowHandle, Win32WindowHandle, WindowsDisplayHandle,
+                        XcbDisplayHandle, XcbWindowHandle, XlibDisplayHandle, XlibWindowHandle};
+
+/// Window reporting whichever handles it was created with.
+struct FakeWindow(RawDisplayHandle, RawWindowHandle);
+
+unsafe impl HasRawDisplayHandle for FakeWindow {
+    fn raw_display_handle(&self) -> RawDisplayHandle {
+        self.0
+    }
+}
+
+unsafe impl HasRawWindowHandle for FakeWindow {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        self.1
+    }
+}
+
+fn xlib() -> (RawDisplayHandle, RawWindowHandle) {
+    let mut display = XlibDisplayHandle::empty();
+    display.display = 0x1000 as *mut _;
+    let mut window = XlibWindowHandle::empty();
+    window.window = 42;
+    (RawDisplayHandle::Xlib(display), RawWindowHandle::Xlib(window))
+}
+
+fn xcb() -> (RawDisplayHandle, RawWindowHandle) {
+    let mut display = XcbDisplayHandle::empty();
+    display.connection = 0x3000 as *mut _;
+    let mut window = XcbWindowHandle::empty();
+    window.window = 42;
+    (RawDisplayHandle::Xcb(display), RawWindowHandle::Xcb(window))
+}
+
+fn wayland() -> (RawDisplayHandle, RawWindowHandle) {
+    let mut display = WaylandDisplayHandle::empty();
+    display.display = 0x4000 as *mut _;
+    let mut window = WaylandWindowHandle::empty();
+    window.surface = 0x5000 as *mut _;
+    (RawDisplayHandle::Wayland(display), RawWindowHandle::Wayland(window))
+}
+
+fn win32() -> (RawDisplayHandle, RawWindowHandle) {
+    let mut window = Win32WindowHandle::empty();
+    window.hwnd = 0x2000 as *mut _;
+    (RawDisplayHandle::Windows(WindowsDisplayHandle::empty()), RawWindowHandle::Win32(window))
+}
+
+fn from_handles(display: RawDisplayHandle, window: RawWindowHandle) -> Result<(), BgfxError> {
+    PlatformData::from_window_handle(&FakeWindow(display, window)).map(|_| ())
+}
+
+#[test]
+fn supported_handles() {
+    for &(display, window) in &[xlib(), xcb(), win32()] {
+        assert!(from_handles(display, window).is_ok());
+    }
+}
+
+#[test]
+fn mismatched_handles() {
+    let (xlib_display, xlib_window) = xlib();
+    let (xcb_display, xcb_window) = xcb();
+    let (wayland_display, _) = wayland();
+    let (win32_display, win32_window) = win32();
+
+    let handles = [(xlib_display, win32_window),
+                   (xcb_display, xlib_window),
+                   (wayland_display, xcb_window),
+                   (win32_display, xlib_window)];
+
+    for &(display, window) in &handles {
+        match from_handles(display, window) {
+            Err(BgfxError::InvalidWindow) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
+
+#[test]
+fn unsupported_handles() {
+    match from_handles(RawDisplayHandle::Web(WebDisplayHandle::empty()),
+                       RawWindowHandle::Web(WebWindowHandle::empty())) {
+        Err(BgfxError::UnsupportedWindowHandle) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "wayland"))]
+fn wayland_unsupported() {
+    let (display, window) = wayland();
+    match from_handles(display, window) {
+        Err(BgfxError::WaylandUnsupported) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    let mut data = PlatformData::new();
+    data.wayland_display(0x4000 as *mut _).wayland_egl_window(0x5000 as *mut _);
+
+    match data.apply() {
+        Err(BgfxError::WaylandUnsupported) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+#[cfg(all(feature = "wayland", target_os = "linux"))]
+fn x11_unsupported_with_wayland() {
+    let mut data = PlatformData::new();
+    data.display(0x1000 as *mut _).window(0x2000 as *mut _);
+
+    match data.apply() {
+        Err(BgfxError::UnsupportedWindowHandle) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
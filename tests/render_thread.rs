@@ -0,0 +1,35 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+use bgfx::*;
+
+// Kept in its own test binary, as claiming the render thread binds it to the claiming thread for
+// the rest of the process.
+#[test]
+fn reclaim_after_shutdown() {
+    for _ in 0..2 {
+        let bgfx = Init::headless().single_threaded(true).init().expect("Failed to initialize");
+        bgfx.frame(false);
+
+        match RenderThread::claim() {
+            Err(BgfxError::AlreadyInitialized) => {}
+            result => panic!("unexpected result: {:?}", result.map(|_| ())),
+        }
+
+        drop(bgfx);
+
+        let render_thread = RenderThread::claim().expect("Failed to reclaim the render thread");
+        assert_eq!(render_thread.render_frame(), RenderFrame::NoContext);
+
+        let bgfx = Init::headless().single_threaded(true).init().expect("Failed to reinitialize");
+        bgfx.frame(false);
+        drop(bgfx);
+        drop(render_thread);
+    }
+
+    // Other threads can not take over the render thread.
+    let claimed = std::thread::spawn(|| RenderThread::claim().is_ok()).join().unwrap();
+    assert!(!claimed);
+}
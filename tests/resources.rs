@@ -0,0 +1,244 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+mod common;
+
+use bgfx::*;
+use common::{FS_CUBES, QUAD_INDICES, QUAD_VERTICES, PosColorVertex, VS_CUBES, WideVertex};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Index data flagging when it is dropped.
+struct TrackedIndices(Vec<u16>, Arc<AtomicBool>);
+
+impl AsRef<[u16]> for TrackedIndices {
+    fn as_ref(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+impl Drop for TrackedIndices {
+    fn drop(&mut self) {
+        self.1.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn shader() {
+    common::with_bgfx(|bgfx| {
+        let vsh = Shader::new(Memory::copy(bgfx, VS_CUBES));
+        let fsh = Shader::new(Memory::reference(bgfx, FS_CUBES));
+        drop(fsh);
+        drop(vsh);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn program() {
+    common::with_bgfx(|bgfx| {
+        let program = common::load_program(bgfx);
+        bgfx.frame(false);
+        drop(program);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn vertex_buffer() {
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        let copied = VertexBuffer::new(Memory::copy(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+        let referenced = VertexBuffer::new(Memory::reference(bgfx, &QUAD_VERTICES),
+                                           &decl,
+                                           BUFFER_NONE);
+        bgfx.frame(false);
+        drop(copied);
+        drop(referenced);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn index_buffer() {
+    common::with_bgfx(|bgfx| {
+        let indices32 = [0u32, 1, 2, 1, 3, 2];
+        let ib16 = IndexBuffer::new(Memory::reference(bgfx, &QUAD_INDICES), BUFFER_NONE);
+        let ib32 = IndexBuffer::new(Memory::copy(bgfx, &indices32), BUFFER_INDEX32);
+        bgfx.frame(false);
+        drop(ib32);
+        drop(ib16);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn from_slice() {
+    common::with_bgfx(|bgfx| {
+        let vb: VertexBuffer<PosColorVertex> =
+            VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+        assert_eq!(vb.num_vertices(), 4);
+
+        let ib16 = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_NONE);
+        let ib32 = IndexBuffer::from_slice(bgfx, &[0u32, 1, 2], BUFFER_NONE);
+        assert_eq!(ib16.num_indices(), 6);
+        assert_eq!(ib32.num_indices(), 3);
+
+        // The index size always follows the index type.
+        let mismatched = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_INDEX32);
+        assert_eq!(mismatched.num_indices(), 6);
+
+        let ib = IndexBuffer::new(Memory::copy(bgfx, &[0u32, 1, 2, 3]), BUFFER_INDEX32);
+        assert_eq!(ib.num_indices(), 4);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+#[should_panic(expected = "does not match the size")]
+fn from_slice_wide_vertex() {
+    let vertices = [WideVertex { x: 0.0, y: 0.0, z: 0.0 }; 3];
+
+    common::with_bgfx(|bgfx| {
+        VertexBuffer::from_slice(bgfx, &vertices, BUFFER_NONE);
+    });
+}
+
+#[test]
+fn owned_memory_released() {
+    common::with_bgfx(|bgfx| {
+        let released = Arc::new(AtomicBool::new(false));
+        let indices = TrackedIndices(QUAD_INDICES.to_vec(), released.clone());
+        let ib = IndexBuffer::new(Memory::owned(bgfx, indices), BUFFER_NONE);
+        bgfx.frame(false);
+        bgfx.frame(false);
+        assert!(released.load(Ordering::SeqCst));
+        drop(ib);
+
+        let shader = Shader::new(Memory::owned(bgfx, VS_CUBES.to_vec().into_boxed_slice()));
+        bgfx.frame(false);
+        drop(shader);
+    });
+}
+
+#[test]
+fn unused_memory_released() {
+    common::with_bgfx(|bgfx| {
+        let released = Arc::new(AtomicBool::new(false));
+        let memory = Memory::owned(bgfx, TrackedIndices(QUAD_INDICES.to_vec(), released.clone()));
+        drop(memory);
+        assert!(released.load(Ordering::SeqCst));
+
+        drop(Memory::copy(bgfx, &QUAD_VERTICES));
+        drop(Memory::reference(bgfx, &QUAD_INDICES));
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn resources_outlived_by_frames() {
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        for _ in 0..4 {
+            let _vb = VertexBuffer::new(Memory::copy(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+            let _ib = IndexBuffer::new(Memory::copy(bgfx, &QUAD_INDICES), BUFFER_NONE);
+            let _program = common::load_program(bgfx);
+            bgfx.frame(false);
+        }
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn dropped_before_shutdown() {
+    // Nothing is destroyed explicitly here; everything is dropped at the end of the closure,
+    // before `with_bgfx` shuts bgfx down.
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        let _program = common::load_program(bgfx);
+        let _vb = VertexBuffer::new(Memory::copy(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+        let _ib = IndexBuffer::new(Memory::copy(bgfx, &QUAD_INDICES), BUFFER_NONE);
+    });
+}
+
+#[test]
+fn shared_resources() {
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        let vb = Shared::new(VertexBuffer::new(Memory::reference(bgfx, &QUAD_VERTICES),
+                                               &decl,
+                                               BUFFER_NONE));
+        let program = Shared::new(common::load_program(bgfx));
+        let owners = vec![(vb.clone(), program.clone()), (vb.clone(), program.clone())];
+        assert_eq!(Shared::strong_count(&vb), 3);
+        assert!(Shared::ptr_eq(&owners[0].0, &owners[1].0));
+
+        common::setup_view(bgfx);
+        drop(vb);
+        drop(program);
+
+        for &(ref vb, ref program) in &owners {
+            bgfx.set_vertex_buffer(0, vb);
+            bgfx.submit(0, program, false);
+        }
+
+        drop(owners);
+        bgfx.frame(false);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn destruction_deferred_until_frame() {
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        let vb = VertexBuffer::new(Memory::reference(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+        let program = common::load_program(bgfx);
+        assert_eq!(bgfx.pending_destructions(), 0);
+
+        common::setup_view(bgfx);
+        bgfx.set_vertex_buffer(0, &vb);
+        bgfx.submit(0, &program, false);
+        drop(vb);
+        drop(program);
+
+        // The program is queued along with both of its shaders.
+        assert_eq!(bgfx.pending_destructions(), 4);
+        bgfx.frame(false);
+        assert_eq!(bgfx.pending_destructions(), 0);
+    });
+}
+
+#[test]
+fn texture_size() {
+    common::with_bgfx(|bgfx| {
+        let texture = Texture::new_2d(Memory::copy(bgfx, &[0u32; 8]),
+                                      2,
+                                      2,
+                                      false,
+                                      2,
+                                      TextureFormat::Rgba8);
+        assert!(texture.is_ok());
+
+        // A 2x2 texture with mips also holds a 1x1 level.
+        let mipped = Texture::new_2d(Memory::copy(bgfx, &[0u32; 5]),
+                                     2,
+                                     2,
+                                     true,
+                                     1,
+                                     TextureFormat::Rgba8);
+        assert!(mipped.is_ok());
+
+        for &(size, has_mips) in &[(3, false), (5, false), (4, true)] {
+            let data = Memory::copy(bgfx, &vec![0u32; size]);
+            match Texture::new_2d(data, 2, 2, has_mips, 1, TextureFormat::Rgba8) {
+                Err(BgfxError::TextureSizeMismatch) => {}
+                result => panic!("unexpected result: {:?}", result.map(|_| ())),
+            }
+        }
+
+        bgfx.frame(false);
+    });
+}
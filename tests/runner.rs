@@ -0,0 +1,66 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+use bgfx::*;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+
+/// Window that sends a number of events, and is then closed.
+struct FakePlatform {
+    remaining: usize,
+}
+
+impl Platform for FakePlatform {
+    type Event = usize;
+
+    fn platform_data(&self) -> Result<PlatformData, BgfxError> {
+        // The noop renderer never touches the back buffer, so no window is needed.
+        let mut data = PlatformData::new();
+        data.back_buffer(ptr::NonNull::dangling().as_ptr());
+        Ok(data)
+    }
+
+    fn init(&self) -> Init {
+        Init::headless()
+    }
+
+    fn pump_events(&mut self, events: &Sender<usize>) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+
+        self.remaining -= 1;
+        events.send(self.remaining).unwrap();
+        true
+    }
+}
+
+// Kept in its own test binary, as `run` claims the calling thread as the render thread for the
+// rest of the process.
+#[test]
+fn run_until_closed() {
+    let received = Arc::new(AtomicUsize::new(0));
+    let api_received = received.clone();
+
+    let result = run(FakePlatform { remaining: 10 }, move |bgfx, events| {
+        bgfx.set_view_rect(0, 0, 0, 320, 240);
+
+        while !events.is_closed() {
+            while events.poll().is_some() {
+                api_received.fetch_add(1, Ordering::SeqCst);
+            }
+
+            bgfx.touch(0);
+            bgfx.frame(false);
+        }
+    });
+
+    result.expect("Failed to run");
+
+    // Every event sent before the window was closed is delivered.
+    assert_eq!(received.load(Ordering::SeqCst), 10);
+}
@@ -0,0 +1,28 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+use bgfx::*;
+
+// Kept in its own test binary, as the thread initializing bgfx in single-threaded mode becomes the
+// render thread for the rest of the process.
+#[test]
+fn single_threaded_frames() {
+    let bgfx = Init::headless()
+                   .single_threaded(true)
+                   .resolution(320, 240)
+                   .init()
+                   .expect("Failed to initialize bgfx");
+
+    // The initializing thread has become the render thread.
+    assert!(RenderThread::claim().is_err());
+
+    bgfx.set_view_rect(0, 0, 0, 320, 240);
+
+    let first = bgfx.frame(false);
+    for i in 1..4 {
+        bgfx.touch(0);
+        assert_eq!(bgfx.frame(false), first + i);
+    }
+}
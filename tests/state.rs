@@ -0,0 +1,177 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+use bgfx::*;
+
+#[test]
+fn presets() {
+    assert_eq!(RenderState::new().to_flags(), STATE_NONE);
+    assert_eq!(RenderState::opaque().to_flags(), STATE_DEFAULT);
+    assert_eq!(RenderState::default(), RenderState::opaque());
+
+    let translucent = STATE_DEFAULT - STATE_DEPTH_WRITE;
+    assert_eq!(RenderState::alpha_blend().to_flags(), translucent | STATE_BLEND_ALPHA);
+    assert_eq!(RenderState::additive().to_flags(), translucent | STATE_BLEND_ADD);
+
+    let premultiplied = Blend::new(BlendFactor::One, BlendFactor::InvSrcAlpha);
+    assert_eq!(RenderState::premultiplied(),
+               *RenderState::alpha_blend().blend(Some(premultiplied)));
+}
+
+#[test]
+fn builder() {
+    let flags = RenderState::new()
+                    .rgb_write(true)
+                    .depth_test(Some(DepthTest::GEqual))
+                    .cull(Some(CullMode::Ccw))
+                    .alpha_ref(0x80)
+                    .primitive(PrimitiveType::Points)
+                    .point_size(4)
+                    .to_flags();
+
+    assert_eq!(flags,
+               STATE_RGB_WRITE | STATE_DEPTH_TEST_GEQUAL | STATE_CULL_CCW |
+               StateFlags::from_bits(0x80 << 40).unwrap() | STATE_PT_POINTS |
+               StateFlags::from_bits(4 << 52).unwrap());
+}
+
+#[test]
+fn blend() {
+    let darken = Blend::new(BlendFactor::One, BlendFactor::One).equation(BlendEquation::Min);
+    assert_eq!(RenderState::new().blend(Some(darken)).to_flags(), STATE_BLEND_DARKEN);
+
+    let separate = Blend::separate(BlendFactor::SrcAlpha,
+                                   BlendFactor::InvSrcAlpha,
+                                   BlendFactor::One,
+                                   BlendFactor::Zero)
+                       .equation_separate(BlendEquation::Add, BlendEquation::Max);
+    let flags = RenderState::new().blend(Some(separate)).to_flags();
+
+    assert_eq!(flags.bits() & STATE_BLEND_MASK.bits(),
+               (5 | 6 << 4 | 2 << 8 | 1 << 12) << 12);
+    assert_eq!(flags.bits() & STATE_BLEND_EQUATION_MASK.bits(), (4 << 3) << 28);
+}
+
+#[test]
+fn round_trip() {
+    let states = [*RenderState::new().primitive(PrimitiveType::TriStrip),
+                  RenderState::opaque(),
+                  RenderState::alpha_blend(),
+                  RenderState::additive(),
+                  RenderState::premultiplied(),
+                  *RenderState::opaque()
+                       .depth_test(Some(DepthTest::Always))
+                       .cull(None)
+                       .msaa(false)
+                       .alpha_ref(255)
+                       .point_size(255),
+                  *RenderState::new().blend(Some(Blend::new(BlendFactor::Factor,
+                                                            BlendFactor::InvFactor)
+                                                     .equation(BlendEquation::RevSub)))];
+
+    for state in states.iter() {
+        assert_eq!(RenderState::from_flags(state.to_flags()), Some(*state));
+    }
+
+    for flags in [STATE_BLEND_MULTIPLY, STATE_BLEND_SCREEN, STATE_BLEND_LINEAR_BURN].iter() {
+        assert_eq!(RenderState::from_flags(*flags).unwrap().to_flags(), *flags);
+    }
+}
+
+#[test]
+fn invalid_flags() {
+    // Blend factor 0 is not a valid factor when blending is enabled.
+    assert_eq!(RenderState::from_flags(STATE_BLEND_ONE), None);
+
+    // Blend equation without blend factors.
+    assert_eq!(RenderState::from_flags(STATE_BLEND_EQUATION_SUB), None);
+
+    // Unused blend factor and primitive type values.
+    assert_eq!(RenderState::from_flags(StateFlags::from_bits(0xeeee << 12).unwrap()), None);
+    assert_eq!(RenderState::from_flags(StateFlags::from_bits(0x7 << 48).unwrap()), None);
+
+    assert_eq!(RenderState::from_flags(STATE_DEFAULT | STATE_RESERVED_MASK), None);
+}
+
+#[test]
+fn debug() {
+    let debug = format!("{:?}", RenderState::from_flags(STATE_DEFAULT | STATE_BLEND_ALPHA));
+    assert!(debug.contains("depth_test: Some(Less)"));
+    assert!(debug.contains("src_rgb: SrcAlpha"));
+    assert!(debug.contains("dst_rgb: InvSrcAlpha"));
+    assert!(debug.contains("cull: Some(Cw)"));
+}
+
+#[test]
+fn independent_blend() {
+    let mut blend = IndependentBlend::new();
+    blend.target(0, Some(TargetBlend::new(BlendFactor::SrcAlpha, BlendFactor::InvSrcAlpha)))
+         .target(2, Some(TargetBlend::new(BlendFactor::One, BlendFactor::One)))
+         .target(3,
+                 Some(TargetBlend::new(BlendFactor::DstColor, BlendFactor::Zero)
+                          .equation(BlendEquation::Min)));
+
+    assert_eq!(blend.to_flags(), STATE_BLEND_INDEPENDENT | STATE_BLEND_ALPHA);
+    assert_eq!(blend.rgba(), (2 | 2 << 4) << 11 | (9 | 1 << 4 | 3 << 8) << 22);
+    assert_eq!(blend.target_blend(1), None);
+
+    let state = *RenderState::opaque().independent_blend(Some(blend));
+    assert_eq!(state.to_flags(), STATE_DEFAULT | STATE_BLEND_INDEPENDENT | STATE_BLEND_ALPHA);
+    assert_eq!(state.rgba(), Some(blend.rgba()));
+    assert_eq!(RenderState::from_flags_rgba(state.to_flags(), blend.rgba()), Some(state));
+    assert_eq!(IndependentBlend::from_flags(state.to_flags(), blend.rgba()), Some(blend));
+
+    // The `rgba` word is needed to decode independent blending.
+    assert_eq!(RenderState::from_flags(state.to_flags()), None);
+
+    // Setting either kind of blending replaces the other.
+    let add = Blend::new(BlendFactor::One, BlendFactor::One);
+    let mut replaced = state;
+    replaced.blend(Some(add));
+    assert_eq!(replaced, *RenderState::opaque().blend(Some(add)));
+    assert_eq!(replaced.rgba(), None);
+}
+
+#[test]
+fn independent_blend_separate_alpha() {
+    let separate = Blend::separate(BlendFactor::SrcAlpha,
+                                   BlendFactor::InvSrcAlpha,
+                                   BlendFactor::One,
+                                   BlendFactor::Zero);
+    let flags = STATE_BLEND_INDEPENDENT | RenderState::new().blend(Some(separate)).to_flags();
+
+    assert_eq!(IndependentBlend::from_flags(flags, 0), None);
+    assert_eq!(IndependentBlend::from_flags(STATE_BLEND_ALPHA, 0), None);
+}
+
+#[test]
+#[should_panic]
+fn independent_blend_out_of_bounds() {
+    IndependentBlend::new().target(4, None);
+}
+
+#[test]
+#[should_panic]
+fn independent_blend_max_equation() {
+    IndependentBlend::new().target(3,
+                                   Some(TargetBlend::new(BlendFactor::One, BlendFactor::One)
+                                            .equation(BlendEquation::Max)));
+}
+
+#[test]
+fn blend_color() {
+    let factor = Blend::new(BlendFactor::Factor, BlendFactor::InvFactor);
+    let state = *RenderState::alpha_blend().blend(Some(factor)).blend_color(0xff8000ff);
+    assert_eq!(state.rgba(), Some(0xff8000ff));
+    assert_eq!(RenderState::from_flags_rgba(state.to_flags(), 0xff8000ff), Some(state));
+    assert_eq!(RenderState::alpha_blend().rgba(), None);
+
+    // Independent blending takes over the `rgba` word.
+    let mut targets = IndependentBlend::new();
+    targets.target(1, Some(TargetBlend::new(BlendFactor::One, BlendFactor::One)));
+    let mut independent = state;
+    independent.independent_blend(Some(targets));
+    assert_eq!(independent.rgba(), Some(targets.rgba()));
+}
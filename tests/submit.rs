@@ -0,0 +1,307 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+mod common;
+
+use bgfx::*;
+use common::{QUAD_INDICES, QUAD_VERTICES};
+
+const IDENTITY: [f32; 16] = [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0,
+                             0.0, 1.0];
+
+#[test]
+fn submit_draws() {
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        let vb = VertexBuffer::new(Memory::reference(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+        let ib = IndexBuffer::new(Memory::reference(bgfx, &QUAD_INDICES), BUFFER_NONE);
+        let program = common::load_program(bgfx);
+
+        common::setup_view(bgfx);
+
+        for _ in 0..3 {
+            bgfx.touch(0);
+
+            for _ in 0..8 {
+                bgfx.set_transform(&IDENTITY);
+                bgfx.set_vertex_buffer(0, &vb);
+                bgfx.set_index_buffer(&ib);
+                bgfx.set_state(STATE_DEFAULT, None);
+                bgfx.submit(0, &program, false);
+            }
+
+            bgfx.frame(false);
+        }
+    });
+}
+
+#[test]
+fn preserve_state() {
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        let vb = VertexBuffer::new(Memory::copy(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+        let ib = IndexBuffer::new(Memory::copy(bgfx, &QUAD_INDICES), BUFFER_NONE);
+        let program = common::load_program(bgfx);
+
+        common::setup_view(bgfx);
+        bgfx.set_vertex_buffer(0, &vb);
+        bgfx.set_index_buffer(&ib);
+        bgfx.set_state(STATE_DEFAULT | STATE_BLEND_ALPHA, None);
+        bgfx.submit(0, &program, true);
+        bgfx.submit(0, &program, false);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn render_state() {
+    common::with_bgfx(|bgfx| {
+        let vb = VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+        let ib = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_NONE);
+        let program = common::load_program(bgfx);
+
+        let mut targets = IndependentBlend::new();
+        targets.target(0, Some(TargetBlend::new(BlendFactor::One, BlendFactor::One)))
+               .target(1, Some(TargetBlend::new(BlendFactor::SrcAlpha, BlendFactor::InvSrcAlpha)));
+        let states = [RenderState::alpha_blend(),
+                      *RenderState::opaque()
+                           .blend(Some(Blend::new(BlendFactor::Factor, BlendFactor::InvFactor)))
+                           .blend_color(0x80808080),
+                      *RenderState::opaque().independent_blend(Some(targets))];
+
+        common::setup_view(bgfx);
+
+        for state in states.iter() {
+            bgfx.set_vertex_buffer(0, &vb);
+            bgfx.set_index_buffer(&ib);
+            bgfx.set_render_state(state);
+            bgfx.submit(0, &program, false);
+        }
+
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn drop_after_submit() {
+    // Resources referenced by draws that were submitted, but not yet rendered, must be safe to
+    // drop before the frame is kicked.
+    common::with_bgfx(|bgfx| {
+        let decl = common::pos_color_decl();
+        common::setup_view(bgfx);
+
+        {
+            let vb = VertexBuffer::new(Memory::copy(bgfx, &QUAD_VERTICES), &decl, BUFFER_NONE);
+            let ib = IndexBuffer::new(Memory::copy(bgfx, &QUAD_INDICES), BUFFER_NONE);
+            let program = common::load_program(bgfx);
+
+            bgfx.set_vertex_buffer(0, &vb);
+            bgfx.set_index_buffer(&ib);
+            bgfx.set_state(STATE_DEFAULT, None);
+            bgfx.submit(0, &program, false);
+        }
+
+        bgfx.frame(false);
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn drop_before_submit() {
+    // Buffers are not borrowed past the calls setting them, but their destruction is deferred
+    // until after the frame, so a draw that was set up before they were dropped still sees them.
+    common::with_bgfx(|bgfx| {
+        let program = common::load_program(bgfx);
+        common::setup_view(bgfx);
+
+        {
+            let vb = VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+            let ib = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_NONE);
+            bgfx.set_vertex_buffer(0, &vb);
+            bgfx.set_index_buffer(&ib);
+        }
+
+        assert_eq!(bgfx.pending_destructions(), 2);
+        bgfx.submit(0, &program, false);
+        bgfx.frame(false);
+        assert_eq!(bgfx.pending_destructions(), 0);
+    });
+}
+
+#[test]
+fn draw() {
+    common::with_bgfx(|bgfx| {
+        let vb = VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+        let ib = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_NONE);
+        let program = common::load_program(bgfx);
+
+        let color = Uniform::new(bgfx, "u_color", UniformType::Vec4, 2);
+        let sampler = Uniform::new(bgfx, "s_texColor", UniformType::Sampler, 1);
+        let texture = Texture::new_2d(Memory::copy(bgfx, &[0xffffffffu32; 4]),
+                                      2,
+                                      2,
+                                      false,
+                                      1,
+                                      TextureFormat::Rgba8)
+                          .unwrap();
+
+        common::setup_view(bgfx);
+
+        for _ in 0..3 {
+            bgfx.touch(0);
+
+            bgfx.draw(0, &program)
+                .vertices(&vb)
+                .indices(&ib)
+                .transform(&IDENTITY)
+                .state(RenderState::alpha_blend())
+                .uniform(&color, &[1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+                .texture(0, &sampler, &texture)
+                .submit()
+                .unwrap();
+
+            bgfx.draw(0, &program).vertices(&vb).index_range(&ib, 3..6).submit().unwrap();
+
+            bgfx.draw(0, &program)
+                .vertex_range(&vb, 1..4)
+                .state(*RenderState::opaque().primitive(PrimitiveType::TriStrip))
+                .submit()
+                .unwrap();
+
+            bgfx.frame(false);
+        }
+    });
+}
+
+#[test]
+#[allow(clippy::reversed_empty_ranges)]
+fn draw_validation() {
+    common::with_bgfx(|bgfx| {
+        let vb = VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+        let ib = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_NONE);
+        let program = common::load_program(bgfx);
+        let color = Uniform::new(bgfx, "u_color", UniformType::Vec4, 1);
+        let sampler = Uniform::new(bgfx, "s_texColor", UniformType::Sampler, 1);
+        let texture = Texture::new_2d(Memory::copy(bgfx, &[0u32; 1]),
+                                      1,
+                                      1,
+                                      false,
+                                      1,
+                                      TextureFormat::Rgba8)
+                          .unwrap();
+
+        match bgfx.draw(0, &program).indices(&ib).submit() {
+            Err(BgfxError::MissingVertices) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match bgfx.draw(0, &program).vertex_range(&vb, 2..5).submit() {
+            Err(BgfxError::RangeOutOfBounds) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match bgfx.draw(0, &program).vertices(&vb).index_range(&ib, 4..2).submit() {
+            Err(BgfxError::RangeOutOfBounds) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        // Too many elements, a partial element, and a sampler set as a value.
+        let values: [&[f32]; 3] = [&[0.0; 8], &[0.0; 3], &[0.0]];
+        for (uniform, value) in [&color, &color, &sampler].iter().zip(values.iter()) {
+            match bgfx.draw(0, &program).vertices(&vb).uniform(uniform, value).submit() {
+                Err(BgfxError::UniformMismatch) => {}
+                result => panic!("unexpected result: {:?}", result),
+            }
+        }
+
+        match bgfx.draw(0, &program).vertices(&vb).texture(0, &color, &texture).submit() {
+            Err(BgfxError::UniformMismatch) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn draw_discards_bgfx_state() {
+    common::with_bgfx(|bgfx| {
+        let vb = VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+        let ib = IndexBuffer::from_slice(bgfx, &[0u16, 1, 2, 3, 4, 5, 6, 7], BUFFER_NONE);
+        let program = common::load_program(bgfx);
+
+        common::setup_view(bgfx);
+
+        // The index buffer reaches past the end of the vertex buffer, and must not be used by the
+        // unindexed draw below.
+        bgfx.set_index_buffer(&ib);
+        bgfx.draw(0, &program)
+            .vertex_range(&vb, 1..4)
+            .state(*RenderState::opaque().primitive(PrimitiveType::TriStrip))
+            .submit()
+            .unwrap();
+
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+fn buffer_ranges() {
+    common::with_bgfx(|bgfx| {
+        // Two quads sharing the same buffers.
+        let vertices: Vec<_> = QUAD_VERTICES.iter().chain(QUAD_VERTICES.iter()).cloned().collect();
+        let indices: Vec<_> = QUAD_INDICES.iter().chain(QUAD_INDICES.iter()).cloned().collect();
+        let vb = VertexBuffer::from_slice(bgfx, &vertices, BUFFER_NONE);
+        let ib = IndexBuffer::from_slice(bgfx, &indices, BUFFER_NONE);
+        let program = common::load_program(bgfx);
+
+        common::setup_view(bgfx);
+
+        for mesh in 0..2 {
+            bgfx.set_vertex_buffer_range(0, &vb, mesh * 4..mesh * 4 + 4);
+            bgfx.set_index_buffer_range(&ib, mesh * 6..mesh * 6 + 6);
+            bgfx.set_state(STATE_DEFAULT, None);
+            bgfx.submit(0, &program, false);
+        }
+
+        // Empty ranges at the very end are valid.
+        bgfx.set_vertex_buffer_range(0, &vb, 8..8);
+        bgfx.set_index_buffer_range(&ib, 12..12);
+        bgfx.submit(0, &program, false);
+
+        bgfx.frame(false);
+    });
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+fn vertex_range_out_of_bounds() {
+    common::with_bgfx(|bgfx| {
+        let vb = VertexBuffer::from_slice(bgfx, &QUAD_VERTICES, BUFFER_NONE);
+        bgfx.set_vertex_buffer_range(0, &vb, 2..5);
+    });
+}
+
+#[test]
+#[should_panic]
+#[cfg(debug_assertions)]
+#[allow(clippy::reversed_empty_ranges)]
+fn index_range_out_of_bounds() {
+    common::with_bgfx(|bgfx| {
+        let ib = IndexBuffer::from_slice(bgfx, &QUAD_INDICES, BUFFER_NONE);
+        bgfx.set_index_buffer_range(&ib, 4..2);
+    });
+}
+
+#[test]
+fn reset() {
+    common::with_bgfx(|bgfx| {
+        bgfx.reset(640, 480, RESET_VSYNC);
+        bgfx.set_view_rect(0, 0, 0, 640, 480);
+        bgfx.touch(0);
+        bgfx.frame(false);
+    });
+}
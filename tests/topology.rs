@@ -0,0 +1,175 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+mod common;
+
+use bgfx::topology::{self, TopologyConvert, TopologySort};
+use common::{PosColorVertex, QUAD_INDICES, QUAD_VERTICES, WideVertex};
+
+#[test]
+fn flip_winding() {
+    common::with_bgfx(|bgfx| {
+        let flipped = topology::convert(bgfx, TopologyConvert::TriListFlipWinding, &QUAD_INDICES);
+        assert_eq!(flipped, [0, 2, 1, 1, 2, 3]);
+
+        let flipped = topology::convert(bgfx, TopologyConvert::TriListFlipWinding, &[0u32, 1, 2]);
+        assert_eq!(flipped, [0, 2, 1]);
+    });
+}
+
+#[test]
+fn tri_list_to_line_list() {
+    common::with_bgfx(|bgfx| {
+        let lines = topology::convert(bgfx, TopologyConvert::TriListToLineList, &QUAD_INDICES);
+
+        // The two triangles share an edge, which is only included once.
+        assert_eq!(lines.len(), 10);
+
+        let mut edges: Vec<_> = lines.chunks(2)
+                                     .map(|edge| (edge[0].min(edge[1]), edge[0].max(edge[1])))
+                                     .collect();
+        edges.sort();
+        assert_eq!(edges, [(0, 1), (0, 2), (1, 2), (1, 3), (2, 3)]);
+    });
+}
+
+#[test]
+fn strips_to_lists() {
+    common::with_bgfx(|bgfx| {
+        let tris = topology::convert(bgfx, TopologyConvert::TriStripToTriList, &[0u16, 1, 2, 3]);
+        assert_eq!(tris.len(), 6);
+        assert_eq!(&tris[..3], &[0, 1, 2]);
+
+        let lines = topology::convert(bgfx, TopologyConvert::LineStripToLineList, &[0u32, 1, 2]);
+        assert_eq!(lines, [0, 1, 1, 2]);
+    });
+}
+
+#[test]
+fn short_inputs() {
+    let conversions = [TopologyConvert::TriListFlipWinding,
+                       TopologyConvert::TriListToLineList,
+                       TopologyConvert::TriStripToTriList,
+                       TopologyConvert::LineStripToLineList];
+    let indices = [0u16, 1, 2];
+
+    common::with_bgfx(|bgfx| {
+        for conversion in conversions.iter() {
+            for len in 0..3 {
+                let converted = topology::convert(bgfx, *conversion, &indices[..len]);
+                if *conversion == TopologyConvert::LineStripToLineList && len == 2 {
+                    assert_eq!(converted, [0, 1]);
+                } else {
+                    assert!(converted.is_empty(), "{:?} of {} indices", conversion, len);
+                }
+            }
+        }
+    });
+}
+
+#[test]
+fn partial_triangles() {
+    let indices = [0u16, 1, 2, 1, 3];
+
+    common::with_bgfx(|bgfx| {
+        // Trailing indices that do not make up a whole triangle are ignored.
+        for len in 4..6 {
+            let flipped = topology::convert(bgfx,
+                                            TopologyConvert::TriListFlipWinding,
+                                            &indices[..len]);
+            assert_eq!(flipped, [0, 2, 1]);
+
+            let lines = topology::convert(bgfx,
+                                          TopologyConvert::TriListToLineList,
+                                          &indices[..len]);
+            assert_eq!(lines.len(), 6);
+        }
+    });
+}
+
+#[test]
+fn sort_tri_list() {
+    // Two triangles at different depths, the nearest one first.
+    let vertices = [PosColorVertex { x: 0.0, y: 0.0, z: 1.0, abgr: 0 },
+                    PosColorVertex { x: 1.0, y: 0.0, z: 1.0, abgr: 0 },
+                    PosColorVertex { x: 0.0, y: 1.0, z: 1.0, abgr: 0 },
+                    PosColorVertex { x: 0.0, y: 0.0, z: 5.0, abgr: 0 },
+                    PosColorVertex { x: 1.0, y: 0.0, z: 5.0, abgr: 0 },
+                    PosColorVertex { x: 0.0, y: 1.0, z: 5.0, abgr: 0 }];
+    let indices = [0u16, 1, 2, 3, 4, 5];
+
+    common::with_bgfx(|bgfx| {
+        let sorted = topology::sort_tri_list(bgfx,
+                                             TopologySort::DirectionBackToFrontAvg,
+                                             &indices,
+                                             [0.0, 0.0, 1.0],
+                                             [0.0, 0.0, 0.0],
+                                             &vertices);
+        assert_eq!(sorted, [3, 4, 5, 0, 1, 2]);
+
+        let sorted = topology::sort_tri_list(bgfx,
+                                             TopologySort::DistanceFrontToBackAvg,
+                                             &sorted,
+                                             [0.0, 0.0, 1.0],
+                                             [0.0, 0.0, 0.0],
+                                             &vertices);
+        assert_eq!(sorted, indices);
+    });
+}
+
+#[test]
+#[should_panic]
+fn sort_tri_list_out_of_bounds() {
+    common::with_bgfx(|bgfx| {
+        topology::sort_tri_list(bgfx,
+                                TopologySort::DirectionBackToFrontAvg,
+                                &QUAD_INDICES,
+                                [0.0, 0.0, 1.0],
+                                [0.0, 0.0, 0.0],
+                                &QUAD_VERTICES[..3]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "whole triangles")]
+fn sort_tri_list_partial_triangle() {
+    common::with_bgfx(|bgfx| {
+        topology::sort_tri_list(bgfx,
+                                TopologySort::DirectionBackToFrontAvg,
+                                &QUAD_INDICES[..4],
+                                [0.0, 0.0, 1.0],
+                                [0.0, 0.0, 0.0],
+                                &QUAD_VERTICES);
+    });
+}
+
+#[test]
+#[should_panic(expected = "does not match the size")]
+fn sort_tri_list_wide_vertex() {
+    let vertices = [WideVertex { x: 0.0, y: 0.0, z: 0.0 }; 3];
+
+    common::with_bgfx(|bgfx| {
+        topology::sort_tri_list(bgfx,
+                                TopologySort::DirectionBackToFrontAvg,
+                                &[0u16, 1, 2],
+                                [0.0, 0.0, 1.0],
+                                [0.0, 0.0, 0.0],
+                                &vertices);
+    });
+}
+
+#[test]
+fn weld_vertices() {
+    let vertices = [PosColorVertex { x: 0.0, y: 0.0, z: 0.0, abgr: 0 },
+                    PosColorVertex { x: 1.0, y: 0.0, z: 0.0, abgr: 0 },
+                    PosColorVertex { x: 0.0, y: 0.0, z: 0.0005, abgr: 0 },
+                    PosColorVertex { x: 1.0, y: 1.0, z: 0.0, abgr: 0 }];
+
+    common::with_bgfx(|bgfx| {
+        let (remap, unique) = topology::weld_vertices(bgfx, &vertices, 0.001);
+        assert_eq!(unique, 3);
+        assert_eq!(remap, [0, 1, 0, 3]);
+    });
+}
@@ -0,0 +1,159 @@
+// Copyright (c) 2015-2016, Johan Sköld.
+// License: http://opensource.org/licenses/ISC
+
+extern crate bgfx;
+
+use bgfx::*;
+use std::collections::HashSet;
+
+fn pos_normal_uv_decl() -> VertexDecl {
+    VertexDecl::new(None)
+        .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+        .add(Attrib::Normal, 4, AttribType::Uint8, ATTRIB_NORMALIZED)
+        .add(Attrib::TexCoord0, 2, AttribType::Half, ATTRIB_NONE)
+        .end()
+}
+
+#[test]
+fn introspection() {
+    let decl = pos_normal_uv_decl();
+    assert_eq!(decl.stride(), 20);
+
+    assert!(decl.has(Attrib::Position));
+    assert!(!decl.has(Attrib::Color0));
+
+    assert_eq!(decl.offset(Attrib::Position), Some(0));
+    assert_eq!(decl.offset(Attrib::Normal), Some(12));
+    assert_eq!(decl.offset(Attrib::TexCoord0), Some(16));
+    assert_eq!(decl.offset(Attrib::Color0), None);
+
+    assert_eq!(decl.attribute(Attrib::Position), Some((3, AttribType::Float, ATTRIB_NONE)));
+    assert_eq!(decl.attribute(Attrib::Normal), Some((4, AttribType::Uint8, ATTRIB_NORMALIZED)));
+    assert_eq!(decl.attribute(Attrib::Color0), None);
+}
+
+#[test]
+fn attrib_types() {
+    let decl = VertexDecl::new(None)
+                   .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+                   .add(Attrib::Normal, 3, AttribType::Uint10, ATTRIB_NORMALIZED)
+                   .add(Attrib::Tangent, 4, AttribType::Int16, ATTRIB_NORMALIZED)
+                   .add(Attrib::Indices, 4, AttribType::Uint8, ATTRIB_AS_INT)
+                   .add(Attrib::Weight, 2, AttribType::Int16, ATTRIB_AS_INT)
+                   .end();
+
+    assert_eq!(decl.offset(Attrib::Position), Some(0));
+    assert_eq!(decl.offset(Attrib::Normal), Some(12));
+    assert_eq!(decl.offset(Attrib::Tangent), Some(16));
+    assert_eq!(decl.offset(Attrib::Indices), Some(24));
+    assert_eq!(decl.offset(Attrib::Weight), Some(28));
+    assert_eq!(decl.stride(), 32);
+
+    assert_eq!(decl.attribute(Attrib::Normal), Some((3, AttribType::Uint10, ATTRIB_NORMALIZED)));
+    assert_eq!(decl.attribute(Attrib::Tangent), Some((4, AttribType::Int16, ATTRIB_NORMALIZED)));
+    assert_eq!(decl.attribute(Attrib::Indices), Some((4, AttribType::Uint8, ATTRIB_AS_INT)));
+    assert_eq!(decl.attribute(Attrib::Weight), Some((2, AttribType::Int16, ATTRIB_AS_INT)));
+}
+
+#[test]
+fn skip() {
+    let decl = VertexDecl::new(None)
+                   .add(Attrib::Position, 2, AttribType::Float, ATTRIB_NONE)
+                   .skip(8)
+                   .add(Attrib::Color0, 4, AttribType::Uint8, ATTRIB_NORMALIZED)
+                   .end();
+
+    assert_eq!(decl.offset(Attrib::Color0), Some(16));
+    assert_eq!(decl.stride(), 20);
+}
+
+#[test]
+#[should_panic]
+fn normalized_float() {
+    VertexDecl::new(None).add(Attrib::Position, 3, AttribType::Float, ATTRIB_NORMALIZED);
+}
+
+#[test]
+#[should_panic]
+fn zero_components() {
+    VertexDecl::new(None).add(Attrib::Position, 0, AttribType::Float, ATTRIB_NONE);
+}
+
+#[test]
+fn equality() {
+    let skipped = VertexDecl::new(None)
+                      .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+                      .skip(4)
+                      .add(Attrib::TexCoord0, 2, AttribType::Half, ATTRIB_NONE)
+                      .end();
+
+    assert_eq!(pos_normal_uv_decl(), pos_normal_uv_decl());
+    assert!(pos_normal_uv_decl() != skipped);
+
+    let mut decls = HashSet::new();
+    decls.insert(pos_normal_uv_decl());
+    decls.insert(pos_normal_uv_decl());
+    decls.insert(skipped);
+    assert_eq!(decls.len(), 2);
+}
+
+#[test]
+fn debug() {
+    let decl = format!("{:?}", pos_normal_uv_decl());
+    assert!(decl.contains("stride: 20"));
+    assert!(decl.contains("Normal: (12, (4, Uint8, ATTRIB_NORMALIZED))"));
+}
+
+#[test]
+fn pack_unpack() {
+    let decl = pos_normal_uv_decl();
+    let mut data = vec![0u8; decl.stride() as usize * 2];
+
+    decl.pack(Attrib::Position, [1.0, -2.0, 0.5, 7.0], false, &mut data, 1);
+    decl.pack(Attrib::Normal, [1.0, 0.0, 0.0, 1.0], true, &mut data, 1);
+    decl.pack(Attrib::TexCoord0, [0.25, 0.75, 0.0, 0.0], false, &mut data, 1);
+
+    assert_eq!(decl.unpack(Attrib::Position, &data, 1), [1.0, -2.0, 0.5, 0.0]);
+    assert_eq!(decl.unpack(Attrib::TexCoord0, &data, 1), [0.25, 0.75, 0.0, 0.0]);
+    assert_eq!(&data[decl.stride() as usize + 12..][..4], &[255, 0, 0, 255]);
+    assert_eq!(decl.unpack(Attrib::Position, &data, 0), [0.0; 4]);
+
+    // Attributes missing from the declaration are neither written nor read.
+    decl.pack(Attrib::Color0, [1.0; 4], false, &mut data, 0);
+    assert_eq!(decl.unpack(Attrib::Color0, &data, 0), [0.0; 4]);
+}
+
+#[test]
+#[should_panic]
+fn pack_out_of_bounds() {
+    let decl = pos_normal_uv_decl();
+    let mut data = vec![0u8; decl.stride() as usize];
+    decl.pack(Attrib::Position, [0.0; 4], false, &mut data, 1);
+}
+
+#[test]
+fn convert() {
+    let src_decl = VertexDecl::new(None)
+                       .add(Attrib::Position, 3, AttribType::Float, ATTRIB_NONE)
+                       .add(Attrib::Normal, 3, AttribType::Float, ATTRIB_NONE)
+                       .add(Attrib::TexCoord0, 2, AttribType::Float, ATTRIB_NONE)
+                       .end();
+    let dst_decl = pos_normal_uv_decl();
+
+    let mut src = vec![0u8; src_decl.stride() as usize * 3];
+    for i in 0..3 {
+        let f = i as f32;
+        src_decl.pack(Attrib::Position, [f, f + 1.0, f + 2.0, 0.0], false, &mut src, i);
+        src_decl.pack(Attrib::Normal, [0.0, 1.0, 0.0, 0.0], false, &mut src, i);
+        src_decl.pack(Attrib::TexCoord0, [0.5, f * 0.25, 0.0, 0.0], false, &mut src, i);
+    }
+
+    let mut dst = vec![0u8; dst_decl.stride() as usize * 3];
+    VertexDecl::convert(&dst_decl, &mut dst, &src_decl, &src, 3);
+
+    for i in 0..3 {
+        let f = i as f32;
+        assert_eq!(dst_decl.unpack(Attrib::Position, &dst, i), [f, f + 1.0, f + 2.0, 0.0]);
+        assert_eq!(dst_decl.unpack(Attrib::TexCoord0, &dst, i), [0.5, f * 0.25, 0.0, 0.0]);
+    }
+}